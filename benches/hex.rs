@@ -0,0 +1,41 @@
+// Compares the `hex` crate with `faster-hex` on large inputs
+//
+// Run with `cargo bench --features faster-hex`.
+
+use std::{hint::black_box, time::Instant};
+
+const SIZES: [usize; 3] = [32, 4 * 1024, 1024 * 1024];
+const TOTAL_BYTES: usize = 64 * 1024 * 1024;
+
+fn bench(name: &str, size: usize, mut f: impl FnMut()) {
+    let iterations = (TOTAL_BYTES / size).max(1);
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let throughput = (iterations * size) as f64 / elapsed.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<20} {:>8} bytes  {:>10.1} MiB/s", name, size, throughput);
+}
+
+fn main() {
+    for size in SIZES {
+        let bytes: Vec<u8> = (0..size).map(|i| (i * 31 + 7) as u8).collect();
+        let encoded = hex::encode(&bytes);
+
+        bench("hex encode", size, || {
+            black_box(hex::encode(black_box(&bytes)));
+        });
+        bench("faster-hex encode", size, || {
+            black_box(faster_hex::hex_string(black_box(&bytes)));
+        });
+        bench("hex decode", size, || {
+            black_box(hex::decode(black_box(&encoded)).unwrap());
+        });
+        bench("faster-hex decode", size, || {
+            let mut out = vec![0; size];
+            faster_hex::hex_decode(black_box(encoded.as_bytes()), &mut out).unwrap();
+            black_box(out);
+        });
+    }
+}
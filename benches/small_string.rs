@@ -0,0 +1,44 @@
+// Compares `to_string` with `to_small_string` on small records
+//
+// Run with `cargo bench --features smallstr`.
+
+use std::{hint::black_box, time::Instant};
+
+use serde::Serialize;
+use serde_json_ext::{Config, to_small_string, to_string};
+use smallstr::SmallString;
+
+const ITERATIONS: usize = 1_000_000;
+
+#[derive(Serialize)]
+struct Record {
+    id: u32,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+fn bench(name: &str, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed();
+    let per_op = elapsed.as_nanos() as f64 / ITERATIONS as f64;
+    println!("{:<20} {:>8.1} ns/op", name, per_op);
+}
+
+fn main() {
+    let config = Config::default().set_bytes_hex().enable_hex_prefix();
+    let record = Record {
+        id: 7,
+        data: vec![0x01, 0x02, 0x03, 0x04],
+    };
+
+    bench("to_string", || {
+        black_box(to_string(black_box(&record), &config).unwrap());
+    });
+    bench("to_small_string", || {
+        let json: SmallString<[u8; 64]> = to_small_string(black_box(&record), &config).unwrap();
+        black_box(json);
+    });
+}
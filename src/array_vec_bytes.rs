@@ -0,0 +1,59 @@
+// Serde helpers for `arrayvec::ArrayVec` byte buffers
+
+use arrayvec::ArrayVec;
+use core::fmt;
+
+use serde::de::{Error, SeqAccess, Visitor};
+
+/// Serializes an `ArrayVec<u8, N>` as bytes, so that it follows the configured bytes format
+///
+/// Use with `#[serde(with = "serde_json_ext::array_vec_bytes")]`.
+pub fn serialize<S, const N: usize>(
+    value: &ArrayVec<u8, N>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_bytes(value)
+}
+
+/// Deserializes an `ArrayVec<u8, N>` from bytes in the configured bytes format
+///
+/// The decoded input must be at most `N` bytes long.
+pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<ArrayVec<u8, N>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct ArrayVecVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ArrayVecVisitor<N> {
+        type Value = ArrayVec<u8, N>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "at most {} bytes", N)
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = ArrayVec::new();
+            while let Some(b) = seq.next_element()? {
+                bytes
+                    .try_push(b)
+                    .map_err(|_| A::Error::invalid_length(N + 1, &self))?;
+            }
+            Ok(bytes)
+        }
+    }
+
+    deserializer.deserialize_bytes(ArrayVecVisitor::<N>)
+}
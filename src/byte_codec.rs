@@ -0,0 +1,17 @@
+// Pluggable byte codecs
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// A string encoding for bytes, selected with `Config::set_bytes_codec`
+///
+/// The built-in bytes formats do not go through this trait, since they honour
+/// options such as the `0x` prefix and padding modes and report decoding
+/// failures as structured errors like `Error::HexDecode`.
+pub trait ByteCodec: fmt::Debug + Send + Sync {
+    /// Encodes bytes as a string
+    fn encode(&self, input: &[u8]) -> String;
+
+    /// Decodes a string produced by `encode`, describing the failure on error
+    fn decode(&self, input: &str) -> Result<Vec<u8>, String>;
+}
@@ -0,0 +1,165 @@
+// Base32 encoding over a 32-character alphabet
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// The RFC 4648 base32 alphabet
+pub(crate) const RFC4648: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// The z-base-32 alphabet, ordered so that the easiest characters to read and
+/// write come first
+pub(crate) const ZBASE32: &[u8; 32] = b"ybndrfg8ejkmcpqxot1uwisza345h769";
+
+/// Errors produced when decoding a base32 string
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Base32Error {
+    /// The input length (or its padding) is not valid base32
+    InvalidLength,
+    /// The input contains a character outside the alphabet
+    InvalidCharacter(char),
+    /// The final character carries bits that do not belong to any byte
+    NonZeroTrailingBits,
+}
+
+impl fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base32Error::InvalidLength => write!(f, "invalid length"),
+            Base32Error::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            Base32Error::NonZeroTrailingBits => write!(f, "non-zero trailing bits"),
+        }
+    }
+}
+
+/// Encodes bytes with the given alphabet, padding with '=' to a multiple of
+/// eight characters when `pad` is set
+pub(crate) fn encode(alphabet: &[u8; 32], input: &[u8], pad: bool) -> String {
+    let mut out = String::with_capacity(input.len().div_ceil(5) * 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &b in input {
+        buffer = (buffer << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(alphabet[((buffer >> bits) & 31) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(alphabet[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+    if pad {
+        while !out.len().is_multiple_of(8) {
+            out.push('=');
+        }
+    }
+
+    out
+}
+
+/// Decodes a string produced by [`encode`] with the same alphabet and padding
+pub(crate) fn decode(alphabet: &[u8; 32], input: &str, pad: bool) -> Result<Vec<u8>, Base32Error> {
+    let data = if pad {
+        let data = input.trim_end_matches('=');
+        if !input.len().is_multiple_of(8) || input.len() - data.len() >= 8 {
+            return Err(Base32Error::InvalidLength);
+        }
+        data
+    } else {
+        input
+    };
+    if !matches!(data.len() % 8, 0 | 2 | 4 | 5 | 7) {
+        return Err(Base32Error::InvalidLength);
+    }
+
+    let mut out = Vec::with_capacity(data.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for c in data.chars() {
+        let value = alphabet
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Base32Error::InvalidCharacter(c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    if buffer & ((1 << bits) - 1) != 0 {
+        return Err(Base32Error::NonZeroTrailingBits);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc4648_vectors() {
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (b"f", "MY======"),
+            (b"fo", "MZXQ===="),
+            (b"foo", "MZXW6==="),
+            (b"foob", "MZXW6YQ="),
+            (b"fooba", "MZXW6YTB"),
+            (b"foobar", "MZXW6YTBOI======"),
+        ];
+
+        for (raw, encoded) in vectors {
+            assert_eq!(encode(RFC4648, raw, true), encoded);
+            assert_eq!(decode(RFC4648, encoded, true).unwrap(), raw);
+        }
+
+        assert_eq!(encode(RFC4648, b"foobar", false), "MZXW6YTBOI");
+        assert_eq!(decode(RFC4648, "MZXW6YTBOI", false).unwrap(), b"foobar");
+
+        assert_eq!(
+            decode(RFC4648, "MY=====", true),
+            Err(Base32Error::InvalidLength)
+        );
+        assert_eq!(
+            decode(RFC4648, "M=======", true),
+            Err(Base32Error::InvalidLength)
+        );
+        assert_eq!(
+            decode(RFC4648, "MZXW6YTB========", true),
+            Err(Base32Error::InvalidLength)
+        );
+        assert_eq!(
+            decode(RFC4648, "my======", true),
+            Err(Base32Error::InvalidCharacter('m'))
+        );
+        assert_eq!(
+            decode(RFC4648, "MZ======", true),
+            Err(Base32Error::NonZeroTrailingBits)
+        );
+    }
+
+    #[test]
+    fn test_zbase32_vectors() {
+        let vectors: [(&[u8], &str); 5] = [
+            (b"", ""),
+            (&[0x00], "yy"),
+            (&[0xf0, 0xbf, 0xc7], "6n9hq"),
+            (&[0xd4, 0x7a, 0x04], "4t7ye"),
+            (b"hello", "pb1sa5dx"),
+        ];
+
+        for (raw, encoded) in vectors {
+            assert_eq!(encode(ZBASE32, raw, false), encoded);
+            assert_eq!(decode(ZBASE32, encoded, false).unwrap(), raw);
+        }
+
+        assert_eq!(
+            decode(ZBASE32, "6N9HQ", false),
+            Err(Base32Error::InvalidCharacter('N'))
+        );
+    }
+}
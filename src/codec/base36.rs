@@ -0,0 +1,109 @@
+// Base36 encoding of a byte string read as a big-endian integer
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// The lowercase base36 alphabet
+const ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Errors produced when decoding a base36 string
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Base36Error {
+    /// The input contains a character outside the alphabet
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for Base36Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base36Error::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+        }
+    }
+}
+
+/// Encodes bytes as a lowercase base36 number
+///
+/// As in base58, each leading zero byte is written as one leading `'0'` and
+/// the remaining bytes as a big-endian integer without leading zeros, so
+/// `[0, 0, 1]` encodes to `"001"` and empty input to `""`.
+pub(crate) fn encode(input: &[u8]) -> String {
+    let zeros = input.iter().take_while(|&&b| b == 0).count();
+
+    // Little-endian base36 digits of the integer after the leading zeros
+    let mut digits: Vec<u8> = Vec::with_capacity((input.len() - zeros) * 8 / 5 + 1);
+    for &b in &input[zeros..] {
+        let mut carry = b as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 36) as u8;
+            carry /= 36;
+        }
+        while carry > 0 {
+            digits.push((carry % 36) as u8);
+            carry /= 36;
+        }
+    }
+
+    let mut out = String::with_capacity(zeros + digits.len());
+    out.extend(core::iter::repeat_n('0', zeros));
+    out.extend(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char));
+    out
+}
+
+/// Decodes a string produced by [`encode`]
+///
+/// Each leading `'0'` becomes one leading zero byte. Only lowercase digits
+/// are accepted.
+pub(crate) fn decode(input: &str) -> Result<Vec<u8>, Base36Error> {
+    let zeros = input.chars().take_while(|&c| c == '0').count();
+
+    // Little-endian bytes of the integer after the leading zeros
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len());
+    for c in input[zeros..].chars() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Base36Error::InvalidCharacter(c))?;
+        let mut carry = value as u32;
+        for byte in bytes.iter_mut() {
+            carry += *byte as u32 * 36;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut out = Vec::with_capacity(zeros + bytes.len());
+    out.resize(zeros, 0);
+    out.extend(bytes.iter().rev());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (&[0], "0"),
+            (&[0, 0], "00"),
+            (&[35], "z"),
+            (&[36], "10"),
+            (&[0, 0, 1, 0], "0074"),
+            (b"hello", "5pzcszu7"),
+        ];
+
+        for (raw, encoded) in vectors {
+            assert_eq!(encode(raw), encoded);
+            assert_eq!(decode(encoded).unwrap(), raw);
+        }
+
+        assert_eq!(decode("5pzcSzu7"), Err(Base36Error::InvalidCharacter('S')));
+        assert_eq!(decode("0-"), Err(Base36Error::InvalidCharacter('-')));
+    }
+}
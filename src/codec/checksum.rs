@@ -0,0 +1,85 @@
+// Mixed-case checksums for hex strings
+
+use alloc::{format, string::String};
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::ChecksumAlgo;
+
+/// Applies a mixed-case checksum to a lowercase hex string without prefix
+///
+/// Each letter is upper-cased when the matching nibble of the Keccak-256
+/// hash is 8 or more. EIP-55 hashes the hex string itself; EIP-1191 hashes
+/// the chain id in decimal followed by the `0x`-prefixed hex string.
+pub(crate) fn apply(algo: ChecksumAlgo, hex_str: String) -> String {
+    let hash = match algo {
+        ChecksumAlgo::None => return hex_str,
+        ChecksumAlgo::Eip55 => keccak256(hex_str.as_bytes()),
+        ChecksumAlgo::Eip1191 { chain_id } => {
+            keccak256(format!("{}0x{}", chain_id, hex_str).as_bytes())
+        }
+    };
+
+    hex_str
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2 % 32] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(input);
+    hasher.finalize(&mut output);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(algo: ChecksumAlgo, expected: &str) {
+        let lower = expected.to_ascii_lowercase();
+        assert_eq!(apply(algo, lower), expected);
+    }
+
+    #[test]
+    fn test_eip55_vectors() {
+        for expected in [
+            "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "fB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "dbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "D1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ] {
+            check(ChecksumAlgo::Eip55, expected);
+        }
+    }
+
+    #[test]
+    fn test_eip1191_vectors() {
+        for expected in [
+            "5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD",
+            "Fb6916095cA1Df60bb79ce92cE3EA74c37c5d359",
+            "DBF03B407c01E7CD3cBea99509D93F8Dddc8C6FB",
+            "D1220A0Cf47c7B9BE7a2e6ba89F429762E7B9adB",
+        ] {
+            check(ChecksumAlgo::Eip1191 { chain_id: 30 }, expected);
+        }
+
+        for expected in [
+            "5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd",
+            "Fb6916095CA1dF60bb79CE92ce3Ea74C37c5D359",
+            "dbF03B407C01E7cd3cbEa99509D93f8dDDc8C6fB",
+            "d1220a0CF47c7B9Be7A2E6Ba89f429762E7b9adB",
+        ] {
+            check(ChecksumAlgo::Eip1191 { chain_id: 31 }, expected);
+        }
+    }
+}
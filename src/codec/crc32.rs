@@ -0,0 +1,44 @@
+// CRC-32 (IEEE 802.3), as used by zlib, PNG and Ethernet
+
+/// Lookup table for the reflected polynomial 0xEDB88320
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 of `input`
+pub(crate) fn checksum(input: &[u8]) -> u32 {
+    !input.iter().fold(!0u32, |crc, &b| {
+        (crc >> 8) ^ TABLE[((crc ^ b as u32) & 0xff) as usize]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_values() {
+        assert_eq!(checksum(b""), 0);
+        assert_eq!(checksum(b"123456789"), 0xcbf4_3926);
+        assert_eq!(
+            checksum(b"The quick brown fox jumps over the lazy dog"),
+            0x414f_a339
+        );
+    }
+}
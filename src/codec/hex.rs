@@ -0,0 +1,74 @@
+// Hex encoding, backed by `faster-hex` when that feature is enabled
+
+use alloc::{string::String, vec::Vec};
+
+/// Encodes bytes as a lowercase hex string
+#[cfg(feature = "faster-hex")]
+pub(crate) fn encode(value: &[u8]) -> String {
+    faster_hex::hex_string(value)
+}
+
+/// Encodes bytes as a lowercase hex string
+#[cfg(not(feature = "faster-hex"))]
+pub(crate) fn encode(value: &[u8]) -> String {
+    hex::encode(value)
+}
+
+/// Decodes a hex string in either case
+///
+/// Invalid input is always reported by the `hex` crate, so errors do not
+/// depend on the backend.
+#[cfg(feature = "faster-hex")]
+pub(crate) fn decode(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    if value.len().is_multiple_of(2) {
+        let mut out = vec![0; value.len() / 2];
+        if faster_hex::hex_decode(value.as_bytes(), &mut out).is_ok() {
+            return Ok(out);
+        }
+    }
+    hex::decode(value)
+}
+
+/// Decodes a hex string in either case
+#[cfg(not(feature = "faster-hex"))]
+pub(crate) fn decode(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(value)
+}
+
+/// Whether `value` is `0x` followed by an even number of hex digits in either case
+pub(crate) fn is_prefixed(value: &str) -> bool {
+    value.strip_prefix("0x").is_some_and(|digits| {
+        digits.len().is_multiple_of(2) && digits.bytes().all(|b| b.is_ascii_hexdigit())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parity_with_hex_crate() {
+        // xorshift64, so that inputs are reproducible without a rand dependency
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..200 {
+            let len = (next() % 300) as usize;
+            let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+
+            let encoded = encode(&bytes);
+            assert_eq!(encoded, hex::encode(&bytes));
+            assert_eq!(decode(&encoded).unwrap(), bytes);
+            assert_eq!(decode(&encoded.to_uppercase()).unwrap(), bytes);
+        }
+
+        for invalid in ["0", "abc", "zz", "0g", "00 1"] {
+            assert_eq!(decode(invalid), hex::decode(invalid));
+        }
+    }
+}
@@ -0,0 +1,11 @@
+// Byte codecs shared by serialization and deserialization
+
+pub(crate) mod base32;
+pub(crate) mod base36;
+#[cfg(feature = "checksum")]
+pub(crate) mod checksum;
+pub(crate) mod crc32;
+pub(crate) mod hex;
+pub(crate) mod protobuf;
+pub(crate) mod rlp;
+pub(crate) mod uuencode;
@@ -0,0 +1,176 @@
+// Protobuf `bytes` field encoding: a varint length followed by the bytes
+
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Errors produced when decoding a length-prefixed byte string
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ProtobufError {
+    /// The input ends inside the varint length
+    TruncatedLength,
+    /// The varint length does not fit in a `u64`
+    LengthOverflow,
+    /// The varint length has redundant trailing zero groups
+    NonCanonical,
+    /// The input has fewer bytes than its length prefix claims
+    Truncated { expected: u64, actual: usize },
+    /// The input has more bytes than its length prefix claims
+    TrailingBytes { expected: u64, actual: usize },
+}
+
+impl fmt::Display for ProtobufError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtobufError::TruncatedLength => write!(f, "input ends inside the varint length"),
+            ProtobufError::LengthOverflow => write!(f, "varint length overflows 64 bits"),
+            ProtobufError::NonCanonical => write!(f, "non-canonical varint length"),
+            ProtobufError::Truncated { expected, actual } => {
+                write!(
+                    f,
+                    "length prefix is {} but only {} bytes follow",
+                    expected, actual
+                )
+            }
+            ProtobufError::TrailingBytes { expected, actual } => {
+                write!(
+                    f,
+                    "length prefix is {} but {} bytes follow",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+
+/// Appends `value` as a base 128 varint, least significant group first
+pub(crate) fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Reads a varint from the start of `input`, returning it and the rest
+///
+/// Encodings with redundant zero groups, such as `[0x80, 0x00]` for 0, are
+/// rejected so that every length has one encoding.
+pub(crate) fn decode_varint(input: &[u8]) -> Result<(u64, &[u8]), ProtobufError> {
+    let mut value = 0u64;
+    for (i, &b) in input.iter().enumerate() {
+        let group = (b & 0x7f) as u64;
+        let shift = 7 * i as u32;
+        // The tenth group holds only the top bit of a u64
+        if shift >= 64 || (shift == 63 && group > 1) {
+            return Err(ProtobufError::LengthOverflow);
+        }
+        value |= group << shift;
+        if b & 0x80 == 0 {
+            if i > 0 && b == 0 {
+                return Err(ProtobufError::NonCanonical);
+            }
+            return Ok((value, &input[i + 1..]));
+        }
+    }
+    Err(ProtobufError::TruncatedLength)
+}
+
+/// Encodes bytes as their varint length followed by the bytes
+pub(crate) fn encode(value: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(10 + value.len());
+    encode_varint(value.len() as u64, &mut out);
+    out.extend_from_slice(value);
+    out
+}
+
+/// Decodes a varint-length-prefixed byte string, requiring the length to match
+pub(crate) fn decode(input: &[u8]) -> Result<&[u8], ProtobufError> {
+    let (len, rest) = decode_varint(input)?;
+    match usize::try_from(len) {
+        Ok(n) if n == rest.len() => Ok(rest),
+        Ok(n) if n < rest.len() => Err(ProtobufError::TrailingBytes {
+            expected: len,
+            actual: rest.len(),
+        }),
+        _ => Err(ProtobufError::Truncated {
+            expected: len,
+            actual: rest.len(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_varint_known_vectors() {
+        for (value, encoded) in [
+            (0u64, vec![0x00]),
+            (1, vec![0x01]),
+            (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]),
+            (300, vec![0xac, 0x02]),
+            (16383, vec![0xff, 0x7f]),
+            (16384, vec![0x80, 0x80, 0x01]),
+            (
+                u64::MAX,
+                vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ] {
+            let mut out = Vec::new();
+            encode_varint(value, &mut out);
+            assert_eq!(out, encoded, "{}", value);
+            assert_eq!(decode_varint(&encoded), Ok((value, &[][..])), "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_decode_round_trip_and_errors() {
+        for value in [
+            vec![],
+            vec![0x00],
+            vec![0x55; 127],
+            vec![0x55; 128],
+            vec![1; 70000],
+        ] {
+            assert_eq!(decode(&encode(&value)).unwrap(), &value[..]);
+        }
+        assert_eq!(encode(&[0xaa; 127])[0], 0x7f);
+        assert_eq!(&encode(&[0xaa; 128])[..2], &[0x80, 0x01]);
+
+        assert_eq!(decode(&[]), Err(ProtobufError::TruncatedLength));
+        assert_eq!(decode(&[0x80]), Err(ProtobufError::TruncatedLength));
+        assert_eq!(decode(&[0x80, 0x00]), Err(ProtobufError::NonCanonical));
+        assert_eq!(decode(&[0xff; 11]), Err(ProtobufError::LengthOverflow));
+        assert_eq!(
+            decode(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]),
+            Err(ProtobufError::LengthOverflow)
+        );
+        assert_eq!(
+            decode(&[0x03, 0x01, 0x02]),
+            Err(ProtobufError::Truncated {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            decode(&[0x01, 0x01, 0x02]),
+            Err(ProtobufError::TrailingBytes {
+                expected: 1,
+                actual: 2
+            })
+        );
+        // A two-byte length of 128 with only 127 bytes after it
+        let mut short = vec![0x80, 0x01];
+        short.extend_from_slice(&[0; 127]);
+        assert_eq!(
+            decode(&short),
+            Err(ProtobufError::Truncated {
+                expected: 128,
+                actual: 127
+            })
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// RLP (Recursive Length Prefix) encoding of byte strings
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+/// Errors produced when decoding an RLP byte string
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RlpError {
+    /// The input is empty
+    Empty,
+    /// The input is an RLP list rather than a byte string
+    UnexpectedList,
+    /// The input is shorter than its length prefix claims
+    Truncated,
+    /// The input has bytes after the encoded string
+    TrailingBytes,
+    /// The input is valid RLP but not in its shortest form
+    NonCanonical,
+}
+
+impl fmt::Display for RlpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RlpError::Empty => write!(f, "empty input"),
+            RlpError::UnexpectedList => write!(f, "expected a byte string, found a list"),
+            RlpError::Truncated => write!(f, "input shorter than length prefix"),
+            RlpError::TrailingBytes => write!(f, "trailing bytes after string"),
+            RlpError::NonCanonical => write!(f, "non-canonical length prefix"),
+        }
+    }
+}
+
+/// Encodes a byte string as RLP
+///
+/// A single byte below 0x80 is its own encoding, strings of up to 55 bytes
+/// are prefixed with `0x80 + len`, and longer strings with `0xb7 + len_of_len`
+/// followed by the big-endian length.
+pub(crate) fn encode(value: &[u8]) -> Vec<u8> {
+    match value {
+        [b] if *b < 0x80 => vec![*b],
+        _ if value.len() <= 55 => {
+            let mut out = Vec::with_capacity(1 + value.len());
+            out.push(0x80 + value.len() as u8);
+            out.extend_from_slice(value);
+            out
+        }
+        _ => {
+            let len = value.len().to_be_bytes();
+            let len = &len[len.iter().position(|b| *b != 0).unwrap_or(len.len() - 1)..];
+            let mut out = Vec::with_capacity(1 + len.len() + value.len());
+            out.push(0xb7 + len.len() as u8);
+            out.extend_from_slice(len);
+            out.extend_from_slice(value);
+            out
+        }
+    }
+}
+
+/// Decodes an RLP byte string, rejecting lists and non-canonical encodings
+pub(crate) fn decode(input: &[u8]) -> Result<&[u8], RlpError> {
+    let (&first, rest) = input.split_first().ok_or(RlpError::Empty)?;
+
+    let payload = match first {
+        0x00..=0x7f => no_trailing(rest, &input[..1])?,
+        0x80..=0xb7 => {
+            let len = (first - 0x80) as usize;
+            let payload = rest.get(..len).ok_or(RlpError::Truncated)?;
+            if len == 1 && payload[0] < 0x80 {
+                return Err(RlpError::NonCanonical);
+            }
+            no_trailing(&rest[len..], payload)?
+        }
+        0xb8..=0xbf => {
+            let len_of_len = (first - 0xb7) as usize;
+            let len_bytes = rest.get(..len_of_len).ok_or(RlpError::Truncated)?;
+            if len_bytes[0] == 0 || len_of_len > size_of::<usize>() {
+                return Err(RlpError::NonCanonical);
+            }
+            let len = len_bytes
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            if len <= 55 {
+                return Err(RlpError::NonCanonical);
+            }
+            let rest = &rest[len_of_len..];
+            let payload = rest.get(..len).ok_or(RlpError::Truncated)?;
+            no_trailing(&rest[len..], payload)?
+        }
+        0xc0..=0xff => return Err(RlpError::UnexpectedList),
+    };
+
+    Ok(payload)
+}
+
+fn no_trailing<'a>(rest: &[u8], payload: &'a [u8]) -> Result<&'a [u8], RlpError> {
+    if rest.is_empty() {
+        Ok(payload)
+    } else {
+        Err(RlpError::TrailingBytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_known_vectors() {
+        assert_eq!(encode(b""), vec![0x80]);
+        assert_eq!(encode(&[0x00]), vec![0x00]);
+        assert_eq!(encode(&[0x7f]), vec![0x7f]);
+        assert_eq!(encode(&[0x80]), vec![0x81, 0x80]);
+        assert_eq!(encode(b"dog"), vec![0x83, b'd', b'o', b'g']);
+
+        let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        assert_eq!(lorem.len(), 56);
+        let mut expected = vec![0xb8, 0x38];
+        expected.extend_from_slice(lorem);
+        assert_eq!(encode(lorem), expected);
+
+        let long = vec![0xaa; 1024];
+        assert_eq!(&encode(&long)[..3], &[0xb9, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn test_decode_round_trip_and_errors() {
+        for value in [
+            vec![],
+            vec![0x00],
+            vec![0x80],
+            vec![0x55; 55],
+            vec![0x55; 56],
+            vec![1; 70000],
+        ] {
+            assert_eq!(decode(&encode(&value)).unwrap(), &value[..]);
+        }
+
+        assert_eq!(decode(&[]), Err(RlpError::Empty));
+        assert_eq!(decode(&[0xc0]), Err(RlpError::UnexpectedList));
+        assert_eq!(decode(&[0x83, b'd', b'o']), Err(RlpError::Truncated));
+        assert_eq!(decode(&[0x00, 0x00]), Err(RlpError::TrailingBytes));
+        assert_eq!(decode(&[0x81, 0x05]), Err(RlpError::NonCanonical));
+        assert_eq!(decode(&[0xb8, 0x01, 0x80]), Err(RlpError::NonCanonical));
+        assert_eq!(decode(&[0xb9, 0x00, 0x38]), Err(RlpError::NonCanonical));
+    }
+}
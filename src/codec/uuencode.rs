@@ -0,0 +1,161 @@
+// Uuencoding of a byte string, without the `begin` and `end` lines
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+/// Bytes per encoded line, as written by the classic `uuencode`
+const LINE_BYTES: usize = 45;
+
+/// Errors produced when decoding a uuencoded string
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum UuencodeError {
+    /// The input contains a character outside `' '..='`'`
+    InvalidCharacter(char),
+    /// A line holds fewer characters than its length character announces
+    TruncatedLine(usize),
+    /// Data follows the zero-length line that ends the input
+    DataAfterEnd,
+}
+
+impl fmt::Display for UuencodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UuencodeError::InvalidCharacter(c) => write!(f, "invalid character {:?}", c),
+            UuencodeError::TruncatedLine(line) => write!(f, "line {} is truncated", line),
+            UuencodeError::DataAfterEnd => f.write_str("data after the final empty line"),
+        }
+    }
+}
+
+/// Maps a 6-bit value to its character, writing zero as a backtick
+fn encode_char(v: u8) -> char {
+    if v == 0 { '`' } else { (v + 32) as char }
+}
+
+/// Maps a character to its 6-bit value, reading both space and backtick as zero
+fn decode_char(c: u8) -> Result<u8, UuencodeError> {
+    match c {
+        b' '..=b'`' => Ok((c - 32) & 0x3f),
+        _ => Err(UuencodeError::InvalidCharacter(c as char)),
+    }
+}
+
+/// Encodes bytes as uuencoded lines
+///
+/// Each line holds a length character and up to 45 bytes as 3-byte groups of
+/// 4 characters, zero-padded to a whole group, and ends with `'\n'`. A
+/// zero-length line `` "`\n" `` ends the output, so empty input encodes to
+/// `` "`\n" ``. Zero is written as a backtick rather than a space, so no
+/// line has trailing spaces to lose.
+pub(crate) fn encode(input: &[u8]) -> String {
+    let lines = input.len().div_ceil(LINE_BYTES);
+    let mut out = String::with_capacity(lines * 62 + 2);
+    for line in input.chunks(LINE_BYTES) {
+        out.push(encode_char(line.len() as u8));
+        for group in line.chunks(3) {
+            let mut b = [0u8; 3];
+            b[..group.len()].copy_from_slice(group);
+            out.push(encode_char(b[0] >> 2));
+            out.push(encode_char(((b[0] & 0x03) << 4) | (b[1] >> 4)));
+            out.push(encode_char(((b[1] & 0x0f) << 2) | (b[2] >> 6)));
+            out.push(encode_char(b[2] & 0x3f));
+        }
+        out.push('\n');
+    }
+    out.push_str("`\n");
+    out
+}
+
+/// Decodes lines produced by [`encode`]
+///
+/// Lines may end with `"\n"` or `"\r\n"`, and the final zero-length line may
+/// be missing. Space and backtick both decode as zero. Characters past the
+/// announced length are ignored, and trailing padding that was stripped, as
+/// some mail systems do with spaces, reads as zero.
+pub(crate) fn decode(input: &str) -> Result<Vec<u8>, UuencodeError> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut ended = false;
+    for (i, line) in input.lines().enumerate() {
+        let line = line.as_bytes();
+        if ended {
+            if line.is_empty() {
+                continue;
+            }
+            return Err(UuencodeError::DataAfterEnd);
+        }
+        let Some((&len, data)) = line.split_first() else {
+            continue;
+        };
+        let len = decode_char(len)? as usize;
+        if len == 0 {
+            ended = true;
+            continue;
+        }
+
+        // Whole groups for the announced bytes, of which only the characters
+        // holding their bits must be present
+        let needed = len.div_ceil(3) * 4;
+        let present = needed.min(data.len());
+        if present < (len * 4).div_ceil(3) {
+            return Err(UuencodeError::TruncatedLine(i + 1));
+        }
+        // A length character announces at most 63 bytes, or 21 groups
+        let mut chars = [0u8; 84];
+        let chars = &mut chars[..needed];
+        for (slot, &c) in chars.iter_mut().zip(&data[..present]) {
+            *slot = decode_char(c)?;
+        }
+
+        let start = out.len();
+        for group in chars.chunks(4) {
+            out.push((group[0] << 2) | (group[1] >> 4));
+            out.push((group[1] << 4) | (group[2] >> 2));
+            out.push((group[2] << 6) | group[3]);
+        }
+        out.truncate(start + len);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vectors() {
+        let all: Vec<u8> = (0..50).collect();
+        let vectors: [(&[u8], &str); 4] = [
+            (b"", "`\n"),
+            (b"Cat", "#0V%T\n`\n"),
+            (
+                b"http://www.wikipedia.org\r\n",
+                "::'1T<#HO+W=W=RYW:6MI<&5D:6$N;W)G#0H`\n`\n",
+            ),
+            (
+                &all,
+                "M``$\"`P0%!@<(\"0H+#`T.#Q`1$A,4%187&!D:&QP='A\\@(2(C)\"4F)R@I*BLL\n\
+                 %+2XO,#$`\n`\n",
+            ),
+        ];
+
+        for (raw, encoded) in vectors {
+            assert_eq!(encode(raw), encoded);
+            assert_eq!(decode(encoded).unwrap(), raw);
+        }
+    }
+
+    #[test]
+    fn test_decode_variants() {
+        // Space for zero, CRLF line endings and no final line
+        assert_eq!(decode("#0V%T\r\n \r\n").unwrap(), b"Cat");
+        assert_eq!(decode("#0V%T").unwrap(), b"Cat");
+        assert_eq!(decode("!80  \n`\n").unwrap(), b"a");
+        // Trailing padding stripped
+        assert_eq!(decode("!80\n`\n").unwrap(), b"a");
+        assert_eq!(decode("").unwrap(), b"");
+
+        assert_eq!(decode("#0V"), Err(UuencodeError::TruncatedLine(1)));
+        assert_eq!(decode("#0v%T"), Err(UuencodeError::InvalidCharacter('v')));
+        assert_eq!(decode("`\n#0V%T\n"), Err(UuencodeError::DataAfterEnd));
+    }
+}
@@ -1,33 +1,512 @@
+use alloc::{string::String, sync::Arc, vec, vec::Vec};
+use core::fmt;
+
+use crate::ByteCodec;
+
 /// Bytes encoding format
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 pub enum BytesFormat {
     /// Default format (array of numbers)
     Default,
     /// Hexadecimal encoding
     Hex,
-    /// Base64 encoding
-    Base64,
+    /// Base64 encoding with the given alphabet and padding
+    Base64(Base64Variant),
     /// Base64 URL-safe encoding
+    #[deprecated(note = "use `BytesFormat::Base64(Base64Variant::URL_SAFE)`")]
     Base64UrlSafe,
+    /// Base64 encoding with a user-provided engine
+    Base64Custom,
+    /// Base64 encoding wrapped at 76 columns with CRLF, as used by MIME
+    Base64Mime,
+    /// Base64 encoding with the bcrypt alphabet `./A-Za-z0-9` and no padding
+    Base64Crypt,
+    /// RLP-encoded byte string, written as hexadecimal
+    Rlp,
+    /// Protobuf-style varint length followed by the bytes, written as hexadecimal
+    Protobuf,
+    /// RFC 4648 base32 encoding with padding
+    Base32,
+    /// z-base-32 encoding without padding, for transcription by people
+    ZBase32,
+    /// Lowercase base36 of the bytes as a big-endian integer, one `0` per leading zero byte
+    Base36,
+    /// Base64 in a data URI, `"data:application/octet-stream;base64,AQI="`
+    DataUri,
+    /// Uuencoded lines of up to 45 bytes, ended by a zero-length line
+    UuEncode,
+    /// MongoDB extended JSON binary, `{"$binary":{"base64":"...","subType":"00"}}`
+    MongoExtended,
+    /// String encoding with a user-provided `ByteCodec`
+    Codec(&'static dyn ByteCodec),
+    /// Array of numbers headed by the byte count, `[3, 1, 2, 255]`
+    LengthPrefixed,
+    /// Node.js `Buffer.toJSON()` object, `{"type":"Buffer","data":[1,2,3]}`
+    NodeBuffer,
+    /// Big-endian unsigned integer for 1 to 8 bytes, hexadecimal otherwise
+    HexOrInteger,
+    /// Object with the byte count and 0x-prefixed hex, `{"len":2,"hex":"0x01ff"}`
+    HexAndLen,
+    /// The bytes as a string, for bytes that hold UTF-8 text
+    Utf8,
+    /// 0x-prefixed hex for up to 8 bytes, standard padded base64 otherwise
+    HexOrBase64,
+}
+
+/// Alphabet and padding of `BytesFormat::Base64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Base64Variant {
+    /// Use the URL-safe alphabet (`-_`) instead of the standard one (`+/`)
+    pub url_safe: bool,
+    /// Write `=` padding
+    pub pad: bool,
+}
+
+impl Base64Variant {
+    /// Standard alphabet with padding
+    pub const STANDARD: Self = Base64Variant {
+        url_safe: false,
+        pad: true,
+    };
+    /// Standard alphabet without padding
+    pub const STANDARD_NO_PAD: Self = Base64Variant {
+        url_safe: false,
+        pad: false,
+    };
+    /// URL-safe alphabet with padding
+    pub const URL_SAFE: Self = Base64Variant {
+        url_safe: true,
+        pad: true,
+    };
+    /// URL-safe alphabet without padding
+    pub const URL_SAFE_NO_PAD: Self = Base64Variant {
+        url_safe: true,
+        pad: false,
+    };
+}
+
+impl PartialEq for BytesFormat {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BytesFormat::Base64(a), BytesFormat::Base64(b)) => a == b,
+            // A codec has no equality of its own, so compare by identity
+            (BytesFormat::Codec(a), BytesFormat::Codec(b)) => core::ptr::addr_eq(*a, *b),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for BytesFormat {}
+
+impl BytesFormat {
+    /// The variant of a plain base64 format, mapping the deprecated variant
+    #[allow(deprecated)]
+    pub(crate) fn base64_variant(self) -> Option<Base64Variant> {
+        match self {
+            BytesFormat::Base64(variant) => Some(variant),
+            BytesFormat::Base64UrlSafe => Some(Base64Variant::URL_SAFE),
+            _ => None,
+        }
+    }
+
+    /// Whether the format always writes bytes as a JSON string
+    pub(crate) fn is_string(self) -> bool {
+        !matches!(
+            self,
+            BytesFormat::Default
+                | BytesFormat::LengthPrefixed
+                | BytesFormat::MongoExtended
+                | BytesFormat::NodeBuffer
+                | BytesFormat::HexOrInteger
+                | BytesFormat::HexAndLen
+        )
+    }
+}
+
+/// Mixed-case checksum applied to hex output
+///
+/// Requires the `checksum` feature.
+#[cfg(feature = "checksum")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksumAlgo {
+    /// Plain lowercase hex
+    None,
+    /// Ethereum EIP-55 checksum
+    Eip55,
+    /// EIP-1191 checksum, which mixes the chain id into the hash
+    Eip1191 {
+        /// Chain id, such as 30 for RSK mainnet
+        chain_id: u64,
+    },
+}
+
+/// JSON representation of `()` and unit structs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitRepr {
+    /// `null`, as serde_json writes it
+    Null,
+    /// An empty object `{}`
+    EmptyObject,
+    /// An empty array `[]`
+    EmptyArray,
+}
+
+/// JSON representation of `Result` fields that use `serde_json_ext::result`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultRepr {
+    /// `{"Ok":...}` or `{"Err":...}`, as serde writes it
+    Tagged,
+    /// `{"ok":...,"error":null}` or `{"ok":null,"error":...}`
+    OkError,
+}
+
+/// Handling of subnormal `f32` and `f64` values on serialize
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubnormalPolicy {
+    /// Write subnormals unchanged, as serde_json does
+    Keep,
+    /// Flush subnormals to zero, keeping their sign
+    Zero,
+    /// Fail serialization on a subnormal
+    Error,
+}
+
+/// Radix of the string elements written by `Config::set_bytes_array_radix`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BytesArrayRadix {
+    /// Two hex digits with a `0x` prefix, `"0xff"`
+    Hex,
+    /// Three octal digits with a `0o` prefix, `"0o377"`
+    Octal,
+    /// Eight binary digits with a `0b` prefix, `"0b11111111"`
+    Binary,
+}
+
+impl BytesArrayRadix {
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            BytesArrayRadix::Hex => "0x",
+            BytesArrayRadix::Octal => "0o",
+            BytesArrayRadix::Binary => "0b",
+        }
+    }
+
+    pub(crate) fn radix(self) -> u32 {
+        match self {
+            BytesArrayRadix::Hex => 16,
+            BytesArrayRadix::Octal => 8,
+            BytesArrayRadix::Binary => 2,
+        }
+    }
+}
+
+/// Object-safe view of a `base64::Engine`
+trait DynEngine: Send + Sync {
+    fn encode(&self, input: &[u8]) -> String;
+    fn decode(&self, input: &str) -> Result<Vec<u8>, base64::DecodeError>;
+}
+
+impl<E> DynEngine for E
+where
+    E: base64::Engine,
+{
+    fn encode(&self, input: &[u8]) -> String {
+        base64::Engine::encode(self, input)
+    }
+
+    fn decode(&self, input: &str) -> Result<Vec<u8>, base64::DecodeError> {
+        base64::Engine::decode(self, input)
+    }
+}
+
+/// A user-provided base64 engine, shared between clones of a `Config`
+#[derive(Clone)]
+pub struct Base64Engine(Arc<dyn DynEngine>);
+
+impl Base64Engine {
+    /// Wraps a `base64::Engine`
+    pub fn new<E>(engine: E) -> Self
+    where
+        E: base64::Engine + 'static,
+    {
+        Base64Engine(Arc::new(engine))
+    }
+
+    pub(crate) fn encode(&self, input: &[u8]) -> String {
+        self.0.encode(input)
+    }
+
+    pub(crate) fn decode(&self, input: &str) -> Result<Vec<u8>, base64::DecodeError> {
+        self.0.decode(input)
+    }
+}
+
+impl fmt::Debug for Base64Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Base64Engine(..)")
+    }
+}
+
+/// A function applied to bytes around encoding, such as compression
+pub type BytesTransformFn = Arc<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// A bytes transform, shared between clones of a `Config`
+#[derive(Clone)]
+pub(crate) struct BytesTransform(pub(crate) BytesTransformFn);
+
+impl fmt::Debug for BytesTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BytesTransform(..)")
+    }
+}
+
+/// Names accepted by `Config::set_bytes_format_name`
+pub const BYTES_FORMAT_NAMES: &[&str] = &[
+    "array",
+    "hex",
+    "hex0x",
+    "base64",
+    "base64url",
+    "base64mime",
+    "base64crypt",
+    "base32",
+    "zbase32",
+    "base36",
+    "uuencode",
+    "rlp",
+    "protobuf",
+    "utf8",
+];
+
+/// Error returned when a `Config` setting is invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Indentation contains bytes other than spaces and tabs
+    InvalidIndent(Vec<u8>),
+    /// `BytesFormat::Base64Custom` is selected but no engine is configured
+    MissingBase64Engine,
+    /// A hex checksum is enabled with a bytes format other than hex
+    #[cfg(feature = "checksum")]
+    ChecksumRequiresHex(BytesFormat),
+    /// A bytes prefix is set but bytes are not always written as strings
+    BytesPrefixRequiresStringFormat,
+    /// A bytes format name is not one of `BYTES_FORMAT_NAMES`
+    UnknownBytesFormatName(String),
+    /// Uppercase hex is enabled along with a mixed-case checksum
+    #[cfg(feature = "checksum")]
+    UppercaseHexWithChecksum(ChecksumAlgo),
+    /// A hex separator is empty or contains hex digits, `x` or whitespace, so
+    /// it cannot be stripped
+    InvalidHexSeparator(String),
+    /// The data URI MIME type contains `,`, which would end it early
+    InvalidDataUriMimeType(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::InvalidIndent(indent) => write!(
+                f,
+                "invalid indent {:?}: only spaces and tabs are allowed",
+                String::from_utf8_lossy(indent)
+            ),
+            ConfigError::MissingBase64Engine => {
+                write!(f, "custom base64 format selected without an engine")
+            }
+            #[cfg(feature = "checksum")]
+            ConfigError::ChecksumRequiresHex(format) => {
+                write!(f, "hex checksums require hex bytes, found {:?}", format)
+            }
+            ConfigError::BytesPrefixRequiresStringFormat => {
+                write!(f, "bytes prefix has no effect on non-string bytes formats")
+            }
+            ConfigError::UnknownBytesFormatName(name) => write!(
+                f,
+                "unknown bytes format {:?}, expected one of: {}",
+                name,
+                BYTES_FORMAT_NAMES.join(", ")
+            ),
+            #[cfg(feature = "checksum")]
+            ConfigError::UppercaseHexWithChecksum(algo) => write!(
+                f,
+                "uppercase hex conflicts with the {:?} checksum, which sets the case itself",
+                algo
+            ),
+            ConfigError::InvalidHexSeparator(separator) => write!(
+                f,
+                "invalid hex separator {:?}: it must be non-empty and contain no hex digits, `x` or whitespace",
+                separator
+            ),
+            ConfigError::InvalidDataUriMimeType(mime_type) => write!(
+                f,
+                "invalid data URI MIME type {:?}: it must not contain ','",
+                mime_type
+            ),
+        }
+    }
 }
 
+impl core::error::Error for ConfigError {}
+
 /// Configuration for serde_json operations
+///
+/// Cloning is cheap: fields that hold buffers, engines or codecs are shared
+/// through `Arc`, so a clone never deep-copies them.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Bytes encoding format
     pub(crate) bytes_format: BytesFormat,
-    /// Enable EIP-55 checksum encoding for hex addresses
-    pub(crate) hex_eip55: bool,
+    /// Mixed-case checksum applied to hex output
+    #[cfg(feature = "checksum")]
+    pub(crate) hex_checksum: ChecksumAlgo,
     /// Enable 0x prefix for hex values
     pub(crate) hex_prefix: bool,
+    /// Write hex bytes in reverse order, as for Bitcoin txids
+    pub(crate) hex_reverse: bool,
+    /// Write hex digits in upper case
+    pub(crate) hex_uppercase: bool,
+    /// Byte lengths that get a 0x prefix in hex output, overriding `hex_prefix`
+    pub(crate) hex_prefix_lengths: Option<Arc<[usize]>>,
+    /// Write empty hex as "" instead of "0x"
+    pub(crate) hex_0x_only_if_nonempty: bool,
+    /// Write a 0x prefix before every group of this many bytes in hex output
+    pub(crate) hex_group_prefix: Option<usize>,
+    /// Separator written between groups of hex digits
+    pub(crate) hex_separator: Option<Arc<str>>,
+    /// Number of hex digits per separated group
+    pub(crate) hex_separator_nibbles: usize,
+    /// Prefix prepended to string-encoded bytes, such as "b64:"
+    pub(crate) bytes_prefix: Option<Arc<str>>,
+    /// Re-encode `collect_str` output that is 0x-prefixed hex as bytes
+    pub(crate) display_hex_as_bytes: bool,
+    /// Escape U+2028 and U+2029 in strings so output is valid JavaScript
+    pub(crate) escape_js_line_separators: bool,
+    /// Write object keys that are identifiers without quotes, as JSON5 allows
+    pub(crate) json5_output: bool,
+    /// Write a comma after the last item of expanded containers in JSON5 output
+    pub(crate) json5_trailing_commas: bool,
+    /// Keep arrays with at most this many scalar elements on one line when pretty-printing
+    pub(crate) compact_arrays_inline: Option<usize>,
+    /// Pad object keys so their values line up when pretty-printing
+    pub(crate) align_object_values: bool,
+    /// Keep containers whose compact form is at most this many bytes on one line when pretty-printing
+    pub(crate) pretty_compact_threshold: Option<usize>,
+    /// Indentation used when pretty-printing
+    pub(crate) indent: Arc<[u8]>,
+    /// Engine used by `BytesFormat::Base64Custom`
+    pub(crate) base64_engine: Option<Base64Engine>,
+    /// MIME type written by `BytesFormat::DataUri`
+    pub(crate) data_uri_mime_type: Arc<str>,
+    /// Applied to bytes before encoding on serialize
+    pub(crate) bytes_transform: Option<BytesTransform>,
+    /// Applied to bytes after decoding on deserialize
+    pub(crate) bytes_untransform: Option<BytesTransform>,
+    /// Append a big-endian CRC-32 to bytes before encoding and verify it on decode
+    pub(crate) bytes_checksum_suffix: bool,
+    /// Accept hexadecimal strings such as "0x1a" for integers on deserialize
+    pub(crate) accept_hex_numbers: bool,
+    /// Ignore input after the first value instead of failing on deserialize
+    pub(crate) allow_trailing_data: bool,
+    /// Maximum nesting depth of arrays, objects and enum variants on deserialize
+    pub(crate) max_depth: Option<usize>,
+    /// Maximum length in bytes of strings read on deserialize
+    pub(crate) max_string_len: Option<usize>,
+    /// Maximum number of bytes read by `from_reader`
+    pub(crate) max_input_bytes: Option<usize>,
+    /// Accept both the standard and URL-safe alphabets when decoding base64
+    pub(crate) base64_accept_both_alphabets: bool,
+    /// Require base64 input padded exactly as the bytes format writes it
+    pub(crate) base64_strict_padding: bool,
+    /// Reject base64 input whose length no base64 string can have
+    pub(crate) validate_base64_length: bool,
+    /// Accept lowercase input when decoding base32
+    pub(crate) bytes_case_insensitive_decode: bool,
+    /// Ignore ASCII whitespace inside hex strings on deserialize
+    pub(crate) hex_ignore_whitespace: bool,
+    /// Write integers as strings with an explicit sign, such as "+5"
+    pub(crate) force_sign: bool,
+    /// Write every number as a string on serialize
+    pub(crate) numbers_as_strings: bool,
+    /// Write bytes in the default array format as signed `i8` values
+    pub(crate) bytes_default_signed: bool,
+    /// Write bytes in the default array format as prefixed strings in this radix
+    pub(crate) bytes_array_radix: Option<BytesArrayRadix>,
+    /// Bytes format used instead of `bytes_format` for empty bytes
+    pub(crate) bytes_empty_format: Option<BytesFormat>,
+    /// String written in place of empty bytes, in any bytes format
+    pub(crate) bytes_empty_placeholder: Option<Arc<str>>,
+    /// Bytes formats tried in turn on deserialize, instead of `bytes_format`
+    pub(crate) bytes_decode_order: Option<Arc<[BytesFormat]>>,
+    /// Bytes formats of newtype structs, keyed by the struct name
+    pub(crate) bytes_type_formats: Option<Arc<[(String, BytesFormat)]>>,
+    /// Bytes formats by nesting depth, sorted by depth
+    pub(crate) bytes_depth_formats: Option<Arc<[(usize, BytesFormat)]>>,
+    /// Representation of unit and unit structs on serialize
+    pub(crate) unit_as: UnitRepr,
+    /// Representation of `Result` fields that use the `result` module
+    pub(crate) result_repr: ResultRepr,
+    /// Handling of subnormal floats on serialize
+    pub(crate) subnormal_policy: SubnormalPolicy,
+    /// Write `-0.0` as `0` on serialize
+    pub(crate) normalize_negative_zero: bool,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             bytes_format: BytesFormat::Default,
-            hex_eip55: false,
+            #[cfg(feature = "checksum")]
+            hex_checksum: ChecksumAlgo::None,
             hex_prefix: false,
+            hex_reverse: false,
+            hex_uppercase: false,
+            hex_prefix_lengths: None,
+            hex_0x_only_if_nonempty: false,
+            hex_group_prefix: None,
+            hex_separator: None,
+            hex_separator_nibbles: 2,
+            bytes_prefix: None,
+            display_hex_as_bytes: false,
+            escape_js_line_separators: false,
+            json5_output: false,
+            json5_trailing_commas: false,
+            compact_arrays_inline: None,
+            align_object_values: false,
+            pretty_compact_threshold: None,
+            indent: Arc::from(&b"  "[..]),
+            base64_engine: None,
+            data_uri_mime_type: "application/octet-stream".into(),
+            bytes_transform: None,
+            bytes_untransform: None,
+            bytes_checksum_suffix: false,
+            accept_hex_numbers: false,
+            allow_trailing_data: false,
+            max_depth: None,
+            max_string_len: None,
+            max_input_bytes: None,
+            base64_accept_both_alphabets: false,
+            base64_strict_padding: true,
+            validate_base64_length: false,
+            bytes_case_insensitive_decode: false,
+            hex_ignore_whitespace: false,
+            force_sign: false,
+            numbers_as_strings: false,
+            bytes_default_signed: false,
+            bytes_array_radix: None,
+            bytes_empty_format: None,
+            bytes_empty_placeholder: None,
+            bytes_decode_order: None,
+            bytes_type_formats: None,
+            bytes_depth_formats: None,
+            unit_as: UnitRepr::Null,
+            result_repr: ResultRepr::Tagged,
+            subnormal_policy: SubnormalPolicy::Keep,
+            normalize_negative_zero: false,
         }
     }
 }
@@ -39,6 +518,88 @@ impl Config {
         self
     }
 
+    /// Writes bytes in the default array format as signed values, so `0xff` is `-1`
+    ///
+    /// On deserialize each element must be in `-128..=127`.
+    pub fn enable_bytes_default_signed(mut self) -> Self {
+        self.bytes_default_signed = true;
+        self
+    }
+
+    /// Writes bytes in the default array format as unsigned values in `0..=255`
+    pub fn disable_bytes_default_signed(mut self) -> Self {
+        self.bytes_default_signed = false;
+        self
+    }
+
+    /// Writes bytes in the default array format as prefixed strings in `radix`
+    ///
+    /// Each element has a fixed number of digits, so `[1, 255]` is written as
+    /// `["0x01","0xff"]` with `BytesArrayRadix::Hex`. On deserialize elements
+    /// may be such strings, in either case and with any number of digits, or
+    /// plain numbers. Takes precedence over `enable_bytes_default_signed`.
+    pub fn set_bytes_array_radix(mut self, radix: BytesArrayRadix) -> Self {
+        self.bytes_array_radix = Some(radix);
+        self
+    }
+
+    /// Writes bytes in the default array format as numbers
+    pub fn clear_bytes_array_radix(mut self) -> Self {
+        self.bytes_array_radix = None;
+        self
+    }
+
+    /// Sets bytes format to an array of one 0x-prefixed hex string per byte, `["0x01","0xff"]`
+    ///
+    /// Shorthand for the default array format with
+    /// `set_bytes_array_radix(BytesArrayRadix::Hex)`, for output meant to be
+    /// read by people such as diagnostics.
+    pub fn set_bytes_hex_as_number_array(mut self) -> Self {
+        self.bytes_format = BytesFormat::Default;
+        self.bytes_array_radix = Some(BytesArrayRadix::Hex);
+        self
+    }
+
+    /// Sets bytes format from a name such as `"hex0x"`, for use in config files
+    ///
+    /// | Name | Equivalent |
+    /// |---|---|
+    /// | `array` | `set_bytes_default()` |
+    /// | `hex` | `set_bytes_hex().disable_hex_prefix()` |
+    /// | `hex0x` | `set_bytes_hex().enable_hex_prefix()` |
+    /// | `base64` | `set_bytes_base64()` |
+    /// | `base64url` | `set_bytes_base64_url_safe()` |
+    /// | `base64mime` | `set_bytes_base64_mime()` |
+    /// | `base64crypt` | `set_bytes_base64_crypt()` |
+    /// | `base32` | `set_bytes_base32()` |
+    /// | `zbase32` | `set_bytes_zbase32()` |
+    /// | `base36` | `set_bytes_base36()` |
+    /// | `uuencode` | `set_bytes_uuencode()` |
+    /// | `rlp` | `set_bytes_rlp()` |
+    /// | `protobuf` | `set_bytes_protobuf()` |
+    /// | `utf8` | `set_bytes_utf8()` |
+    ///
+    /// Returns `ConfigError::UnknownBytesFormatName` for any other name.
+    pub fn set_bytes_format_name(self, name: &str) -> Result<Self, ConfigError> {
+        Ok(match name {
+            "array" => self.set_bytes_default(),
+            "hex" => self.set_bytes_hex().disable_hex_prefix(),
+            "hex0x" => self.set_bytes_hex().enable_hex_prefix(),
+            "base64" => self.set_bytes_base64(),
+            "base64url" => self.set_bytes_base64_url_safe(),
+            "base64mime" => self.set_bytes_base64_mime(),
+            "base64crypt" => self.set_bytes_base64_crypt(),
+            "base32" => self.set_bytes_base32(),
+            "zbase32" => self.set_bytes_zbase32(),
+            "base36" => self.set_bytes_base36(),
+            "uuencode" => self.set_bytes_uuencode(),
+            "rlp" => self.set_bytes_rlp(),
+            "protobuf" => self.set_bytes_protobuf(),
+            "utf8" => self.set_bytes_utf8(),
+            _ => return Err(ConfigError::UnknownBytesFormatName(name.into())),
+        })
+    }
+
     /// Sets bytes format to hexadecimal
     pub fn set_bytes_hex(mut self) -> Self {
         self.bytes_format = BytesFormat::Hex;
@@ -46,26 +607,485 @@ impl Config {
     }
 
     /// Sets bytes format to base64
-    pub fn set_bytes_base64(mut self) -> Self {
-        self.bytes_format = BytesFormat::Base64;
-        self
+    pub fn set_bytes_base64(self) -> Self {
+        self.set_bytes_b64_variant(Base64Variant::STANDARD)
     }
 
     /// Sets bytes format to base64 URL-safe
-    pub fn set_bytes_base64_url_safe(mut self) -> Self {
-        self.bytes_format = BytesFormat::Base64UrlSafe;
+    pub fn set_bytes_base64_url_safe(self) -> Self {
+        self.set_bytes_b64_variant(Base64Variant::URL_SAFE)
+    }
+
+    /// Sets bytes format to base64 with the standard alphabet and no padding
+    ///
+    /// Padded input is rejected on deserialize unless
+    /// `disable_base64_strict_padding` is set.
+    pub fn set_bytes_base64_standard_no_pad(self) -> Self {
+        self.set_bytes_b64_variant(Base64Variant::STANDARD_NO_PAD)
+    }
+
+    /// Sets bytes format to base64 with the alphabet and padding of `variant`
+    ///
+    /// Input must be padded as `variant` writes it on deserialize unless
+    /// `disable_base64_strict_padding` is set.
+    pub fn set_bytes_b64_variant(mut self, variant: Base64Variant) -> Self {
+        self.bytes_format = BytesFormat::Base64(variant);
+        self
+    }
+
+    /// Sets bytes format to MIME base64, wrapped at 76 columns with CRLF
+    ///
+    /// Whitespace in the input is ignored on deserialize.
+    pub fn set_bytes_base64_mime(mut self) -> Self {
+        self.bytes_format = BytesFormat::Base64Mime;
+        self
+    }
+
+    /// Sets bytes format to base64 with the bcrypt alphabet `./A-Za-z0-9`
+    ///
+    /// Output is unpadded, as in password hashes; padded input is rejected.
+    pub fn set_bytes_base64_crypt(mut self) -> Self {
+        self.bytes_format = BytesFormat::Base64Crypt;
+        self
+    }
+
+    /// Sets bytes format to base64 using a custom engine
+    ///
+    /// The engine is used for both encoding and decoding, which allows
+    /// alphabets and padding rules not covered by the other formats.
+    pub fn set_bytes_base64_custom_engine<E>(mut self, engine: E) -> Self
+    where
+        E: base64::Engine + 'static,
+    {
+        self.bytes_format = BytesFormat::Base64Custom;
+        self.base64_engine = Some(Base64Engine::new(engine));
+        self
+    }
+
+    /// Sets bytes format to strings encoded by a custom `ByteCodec`
+    ///
+    /// The codec is carried by `BytesFormat::Codec`, which stays `Copy`, so it
+    /// is borrowed for `'static`, typically from a `static` item. The bytes
+    /// prefix still applies around the encoded string.
+    pub fn set_bytes_codec(mut self, codec: &'static dyn ByteCodec) -> Self {
+        self.bytes_format = BytesFormat::Codec(codec);
+        self
+    }
+
+    /// Sets bytes format to an array of numbers headed by the byte count
+    ///
+    /// Deserializing fails if the count does not match the bytes that follow.
+    pub fn set_bytes_length_prefixed(mut self) -> Self {
+        self.bytes_format = BytesFormat::LengthPrefixed;
+        self
+    }
+
+    /// Sets bytes format to Node.js `Buffer.toJSON()` objects
+    ///
+    /// Deserializing fails unless the `type` field is `"Buffer"`.
+    pub fn set_bytes_node_buffer(mut self) -> Self {
+        self.bytes_format = BytesFormat::NodeBuffer;
+        self
+    }
+
+    /// Sets bytes format to a JSON number for 1 to 8 bytes and hex otherwise
+    ///
+    /// Bytes of length 1 to 8 are written as the big-endian unsigned integer
+    /// they encode, so `[0x01, 0x00]` is `256`. Empty and longer bytes are
+    /// written as hex strings with the hex settings.
+    ///
+    /// On deserialize a number becomes its minimal big-endian bytes, at least
+    /// one byte long: `256` is `[0x01, 0x00]` and `0` is `[0x00]`. Leading
+    /// zero bytes are therefore lost, so `[0x00, 0x01]` reads back as `[0x01]`.
+    /// Strings are decoded as hex. Integers cannot carry a bytes prefix, so
+    /// `validate` rejects one with this format.
+    pub fn set_bytes_hex_or_integer(mut self) -> Self {
+        self.bytes_format = BytesFormat::HexOrInteger;
+        self
+    }
+
+    /// Sets bytes format to hex for short bytes and base64 for longer ones
+    ///
+    /// Bytes of length 0 to 8 are written as lowercase hex with a `0x`
+    /// prefix, `"0x0102"`, and longer bytes as standard padded base64. The
+    /// hex settings (uppercase, checksums, separators) do not apply.
+    ///
+    /// On deserialize a string is read as hex if it is `0x` followed by an
+    /// even number of hex digits, and as base64 otherwise. Some base64 strings
+    /// have that shape, such as `"0x0123456789"` for 9 bytes; those bytes are
+    /// written as hex instead, so every output reads back unchanged.
+    pub fn set_bytes_format_auto_by_length(mut self) -> Self {
+        self.bytes_format = BytesFormat::HexOrBase64;
+        self
+    }
+
+    /// Sets bytes format to objects holding the byte count and 0x-prefixed hex
+    ///
+    /// On deserialize `len` is optional; when present it must match the
+    /// decoded bytes.
+    pub fn set_bytes_hex_and_len(mut self) -> Self {
+        self.bytes_format = BytesFormat::HexAndLen;
+        self
+    }
+
+    /// Sets bytes format to RLP, written as hexadecimal
+    ///
+    /// Bytes are RLP-encoded as a single byte string and the encoding is
+    /// written with the hex settings (`0x` prefix, bytes prefix). Decoding
+    /// rejects RLP lists and non-canonical length prefixes.
+    pub fn set_bytes_rlp(mut self) -> Self {
+        self.bytes_format = BytesFormat::Rlp;
+        self
+    }
+
+    /// Sets bytes format to a protobuf `bytes` field, written as hexadecimal
+    ///
+    /// Bytes are prefixed with their length as a base 128 varint, so `[0x01,
+    /// 0x02]` is written `"020102"`, and the result is written with the hex
+    /// settings (`0x` prefix, bytes prefix). Decoding requires the length to
+    /// match the bytes that follow and rejects overlong varints.
+    pub fn set_bytes_protobuf(mut self) -> Self {
+        self.bytes_format = BytesFormat::Protobuf;
+        self
+    }
+
+    /// Sets bytes format to RFC 4648 base32 with padding
+    pub fn set_bytes_base32(mut self) -> Self {
+        self.bytes_format = BytesFormat::Base32;
+        self
+    }
+
+    /// Sets bytes format to z-base-32
+    ///
+    /// z-base-32 uses the lowercase alphabet `ybndrfg8ejkmcpqxot1uwisza345h769`,
+    /// chosen to be easy to read aloud and type, and writes no padding: five
+    /// bytes take eight characters and a final partial group takes only as
+    /// many characters as its bits need. Decoding accepts uppercase input only
+    /// with `enable_bytes_case_insensitive_decode`.
+    pub fn set_bytes_zbase32(mut self) -> Self {
+        self.bytes_format = BytesFormat::ZBase32;
+        self
+    }
+
+    /// Sets bytes format to lowercase base36
+    ///
+    /// The bytes are read as a big-endian integer and written in base36 with
+    /// the digits `0-9a-z`. As in base58, each leading zero byte is written as
+    /// a single leading `0`, so `[0, 0, 1]` encodes to `"001"` and leading
+    /// zeros survive a round trip. Decoding accepts uppercase digits only with
+    /// `enable_bytes_case_insensitive_decode`.
+    pub fn set_bytes_base36(mut self) -> Self {
+        self.bytes_format = BytesFormat::Base36;
+        self
+    }
+
+    /// Sets bytes format to base64 data URIs with the given MIME type
+    ///
+    /// Bytes are written as `"data:<mime_type>;base64,<standard base64>"`,
+    /// for example `"data:image/png;base64,iVBORw0K..."`, which can be used
+    /// directly as the `src` of an HTML image. The MIME type is written as
+    /// given and defaults to `application/octet-stream`. It must not contain
+    /// `,`, which ends the media type, as `validate` reports. Decoding requires
+    /// the `data:` scheme and the `;base64` marker and accepts any MIME type.
+    pub fn set_bytes_data_uri(mut self, mime_type: &str) -> Self {
+        self.bytes_format = BytesFormat::DataUri;
+        self.data_uri_mime_type = mime_type.into();
+        self
+    }
+
+    /// Sets bytes format to uuencoding, for interop with legacy systems
+    ///
+    /// The bytes are written as the lines between `begin` and `end` of a
+    /// uuencoded file: each line has a length character and up to 45 bytes in
+    /// groups of 3 bytes to 4 characters, and a zero-length line ends the
+    /// string. Zero is written as a backtick. Decoding also accepts spaces for
+    /// zero, `\r\n` line endings and a missing final line.
+    pub fn set_bytes_uuencode(mut self) -> Self {
+        self.bytes_format = BytesFormat::UuEncode;
+        self
+    }
+
+    /// Sets bytes format to plain strings holding the bytes as UTF-8
+    ///
+    /// Suits byte fields that usually hold text: a string such as `"Hello"`
+    /// deserializes to its UTF-8 bytes `[72, 101, 108, 108, 111]`. Bytes that
+    /// are not valid UTF-8 fail to serialize.
+    pub fn set_bytes_utf8(mut self) -> Self {
+        self.bytes_format = BytesFormat::Utf8;
+        self
+    }
+
+    /// Sets bytes format to MongoDB extended JSON binary objects
+    ///
+    /// Bytes are written with the generic binary subtype "00"; the subtype is
+    /// ignored on deserialize.
+    pub fn set_bytes_mongo_extended(mut self) -> Self {
+        self.bytes_format = BytesFormat::MongoExtended;
+        self
+    }
+
+    /// Writes empty bytes in `format` instead of the configured bytes format
+    ///
+    /// For example, base64 bytes with `BytesFormat::Default` for empty values
+    /// write `"AQI="` but `[]`. Deserialize accepts empty bytes in either
+    /// format.
+    pub fn set_bytes_empty_format(mut self, format: BytesFormat) -> Self {
+        self.bytes_empty_format = Some(format);
+        self
+    }
+
+    /// Writes empty bytes in the configured bytes format
+    pub fn clear_bytes_empty_format(mut self) -> Self {
+        self.bytes_empty_format = None;
+        self
+    }
+
+    /// Writes empty bytes as the string `placeholder`, such as `"0x00"`
+    ///
+    /// This is for consumers that reject an empty encoding like `"0x"`. On
+    /// deserialize, exactly `placeholder` reads as empty bytes and anything
+    /// else is decoded in the configured bytes format. Non-empty bytes whose
+    /// encoding equals `placeholder` fail to serialize rather than be read
+    /// back as empty: with hex and the prefix "0x", a placeholder of `"0x00"`
+    /// makes `[0x00]` an error, while `"empty"` keeps both representable.
+    ///
+    /// The placeholder stands for the bytes before any `set_bytes_transform`
+    /// or checksum suffix and takes precedence over `set_bytes_empty_format`.
+    pub fn set_bytes_empty_placeholder(mut self, placeholder: &str) -> Self {
+        self.bytes_empty_placeholder = Some(placeholder.into());
+        self
+    }
+
+    /// Writes empty bytes in the bytes format, without a placeholder
+    pub fn clear_bytes_empty_placeholder(mut self) -> Self {
+        self.bytes_empty_placeholder = None;
+        self
+    }
+
+    /// Requires base64 input to be padded exactly as the bytes format writes it
+    ///
+    /// This is the default: padded base64 variants and `Base64Mime` reject
+    /// unpadded input, and unpadded variants reject padded input. Custom
+    /// engines and `Base64Crypt` keep their own rules.
+    pub fn enable_base64_strict_padding(mut self) -> Self {
+        self.base64_strict_padding = true;
+        self
+    }
+
+    /// Accepts base64 input with or without padding on deserialize
+    ///
+    /// Use this for producers that drop or add `=` padding, for example when
+    /// reading a `[u8; N]` with `bytes_array` from unpadded base64.
+    pub fn disable_base64_strict_padding(mut self) -> Self {
+        self.base64_strict_padding = false;
+        self
+    }
+
+    /// Rejects base64 input with an invalid length before decoding it
+    ///
+    /// Base64 strings, padded or not, never have a length one more than a
+    /// multiple of 4, not counting whitespace. Such input then fails with an
+    /// error naming its length instead of a generic decode error.
+    pub fn enable_base64_length_validation(mut self) -> Self {
+        self.validate_base64_length = true;
+        self
+    }
+
+    /// Leaves base64 length errors to the decoder
+    pub fn disable_base64_length_validation(mut self) -> Self {
+        self.validate_base64_length = false;
+        self
+    }
+
+    /// Writes `Display` output that is 0x-prefixed hex in the configured bytes format
+    ///
+    /// Types that serialize through `collect_str` reach the serializer as
+    /// text, so the bytes format normally never applies to them. With this
+    /// option, text such as `"0xabcd"` is decoded and written as bytes;
+    /// anything else, including unprefixed hex and base64, is left as-is.
+    pub fn enable_display_hex_as_bytes(mut self) -> Self {
+        self.display_hex_as_bytes = true;
+        self
+    }
+
+    /// Writes `Display` output unchanged
+    pub fn disable_display_hex_as_bytes(mut self) -> Self {
+        self.display_hex_as_bytes = false;
+        self
+    }
+
+    /// Transforms bytes before they are encoded on serialize
+    ///
+    /// The transform sees the raw bytes and its output is what gets encoded,
+    /// so it can compress or encrypt payloads. Pair it with
+    /// `set_bytes_untransform` to restore the bytes on deserialize.
+    pub fn set_bytes_transform(mut self, transform: BytesTransformFn) -> Self {
+        self.bytes_transform = Some(BytesTransform(transform));
+        self
+    }
+
+    /// Encodes bytes without transforming them on serialize
+    pub fn clear_bytes_transform(mut self) -> Self {
+        self.bytes_transform = None;
+        self
+    }
+
+    /// Transforms bytes after they are decoded on deserialize
+    ///
+    /// This is the inverse of `set_bytes_transform`. Length checks such as
+    /// those of `bytes_array` apply to the untransformed bytes.
+    pub fn set_bytes_untransform(mut self, untransform: BytesTransformFn) -> Self {
+        self.bytes_untransform = Some(BytesTransform(untransform));
+        self
+    }
+
+    /// Hands decoded bytes to the target unchanged on deserialize
+    pub fn clear_bytes_untransform(mut self) -> Self {
+        self.bytes_untransform = None;
+        self
+    }
+
+    /// Appends a CRC-32 of the bytes before encoding them
+    ///
+    /// The CRC-32 (IEEE 802.3, as in zlib and PNG) is appended as 4 bytes in
+    /// big-endian order, so `b"123456789"` is encoded as those bytes followed
+    /// by `cb f4 39 26`. On deserialize the last 4 decoded bytes are stripped
+    /// and checked, failing with `Error::ChecksumMismatch`. The checksum covers
+    /// the bytes after `set_bytes_transform` and is checked before
+    /// `set_bytes_untransform`.
+    pub fn set_bytes_checksum_suffix(mut self) -> Self {
+        self.bytes_checksum_suffix = true;
+        self
+    }
+
+    /// Encodes bytes without a checksum suffix
+    pub fn clear_bytes_checksum_suffix(mut self) -> Self {
+        self.bytes_checksum_suffix = false;
+        self
+    }
+
+    /// Tries each bytes format of `order` in turn on deserialize
+    ///
+    /// The first format that decodes wins, so put stricter formats first
+    /// (a hex string is often valid base64 too). Serialization still uses the
+    /// single configured format. Each byte field is buffered as a
+    /// `serde_json::Value` before decoding.
+    pub fn set_bytes_decode_order(mut self, order: Vec<BytesFormat>) -> Self {
+        self.bytes_decode_order = Some(order.into());
+        self
+    }
+
+    /// Decodes bytes with the configured format only
+    pub fn clear_bytes_decode_order(mut self) -> Self {
+        self.bytes_decode_order = None;
+        self
+    }
+
+    /// Uses `format` for the bytes inside newtype structs named `name`, such as `"Hash"`
+    ///
+    /// Serde identifies a type only by the name given to
+    /// `serialize_newtype_struct`, which for `struct Hash(...)` is `"Hash"`
+    /// without its module path, so every newtype with that name is affected.
+    /// The newtype must hold bytes, such as a `Vec<u8>` with
+    /// `#[serde(with = "serde_bytes")]`. On deserialize its contents are
+    /// decoded in `format` before they reach the newtype. Other settings, such
+    /// as the hex prefix, are shared with the rest of the document.
+    pub fn set_bytes_format_for_type_name(mut self, name: &str, format: BytesFormat) -> Self {
+        let mut formats: Vec<_> = self
+            .bytes_type_formats
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|(n, _)| n != name)
+            .cloned()
+            .collect();
+        formats.push((name.into(), format));
+        self.bytes_type_formats = Some(formats.into());
+        self
+    }
+
+    /// Uses `format` for the bytes inside newtype structs with the name of `T`
+    ///
+    /// Registers the last path segment of `core::any::type_name::<T>()`
+    /// without generic arguments, so `my_crate::Hash` registers `"Hash"`; see
+    /// [`Config::set_bytes_format_for_type_name`]. Types whose serde name
+    /// differs, for example through `#[serde(rename)]`, must be registered by
+    /// name.
+    pub fn set_bytes_format_per_type<T: ?Sized>(self, format: BytesFormat) -> Self {
+        let path = core::any::type_name::<T>();
+        let path = path.split('<').next().unwrap_or(path);
+        let name = path.rsplit("::").next().unwrap_or(path);
+        self.set_bytes_format_for_type_name(name, format)
+    }
+
+    /// Uses the configured bytes format inside every newtype struct
+    pub fn clear_bytes_formats_per_type(mut self) -> Self {
+        self.bytes_type_formats = None;
+        self
+    }
+
+    /// Uses `format` for bytes nested `depth` or more levels deep
+    ///
+    /// The depth of a value is the number of JSON arrays and objects around
+    /// it: a top-level value has depth 0, the fields of a top-level struct
+    /// have depth 1 and the fields of a struct inside it have depth 2. An
+    /// externally tagged enum variant adds the object holding its tag. Bytes
+    /// use the format registered for the greatest depth not above their own,
+    /// or the configured bytes format if there is none. With hex configured
+    /// and base64 registered for depth 2, the fields of a top-level struct are
+    /// written as hex and everything deeper as base64. A
+    /// format set with [`Config::set_bytes_format_for_type_name`] takes
+    /// precedence inside its newtype.
+    pub fn set_bytes_format_for_depth(mut self, depth: usize, format: BytesFormat) -> Self {
+        let mut formats: Vec<_> = self
+            .bytes_depth_formats
+            .as_deref()
+            .unwrap_or_default()
+            .iter()
+            .filter(|(d, _)| *d != depth)
+            .copied()
+            .collect();
+        formats.push((depth, format));
+        formats.sort_by_key(|(d, _)| *d);
+        self.bytes_depth_formats = Some(formats.into());
+        self
+    }
+
+    /// Uses the configured bytes format at every nesting depth
+    pub fn clear_bytes_formats_for_depth(mut self) -> Self {
+        self.bytes_depth_formats = None;
+        self
+    }
+
+    /// Returns a copy of this config with only the bytes format changed
+    ///
+    /// `BytesFormat::Base64Custom` reuses the engine already configured, if any.
+    pub fn clone_with_bytes_format(&self, bytes_format: BytesFormat) -> Self {
+        Config {
+            bytes_format,
+            ..self.clone()
+        }
+    }
+
+    /// Sets bytes format to hexadecimal with a mixed-case checksum
+    #[cfg(feature = "checksum")]
+    pub fn set_bytes_hex_checksum(mut self, algo: ChecksumAlgo) -> Self {
+        self.bytes_format = BytesFormat::Hex;
+        self.hex_checksum = algo;
         self
     }
 
     /// Enables EIP-55 checksum encoding for hex addresses
+    #[cfg(feature = "checksum")]
     pub fn enable_hex_eip55(mut self) -> Self {
-        self.hex_eip55 = true;
+        self.hex_checksum = ChecksumAlgo::Eip55;
         self
     }
 
     /// Disables EIP-55 checksum encoding for hex addresses
+    #[cfg(feature = "checksum")]
     pub fn disable_hex_eip55(mut self) -> Self {
-        self.hex_eip55 = false;
+        self.hex_checksum = ChecksumAlgo::None;
         self
     }
 
@@ -80,4 +1100,1027 @@ impl Config {
         self.hex_prefix = false;
         self
     }
+
+    /// Writes hex bytes in reverse order and reverses them again on deserialize
+    ///
+    /// Some chains display hashes this way, such as Bitcoin txids. The
+    /// prefix and checksum apply to the reversed hex string.
+    pub fn enable_hex_reverse(mut self) -> Self {
+        self.hex_reverse = true;
+        self
+    }
+
+    /// Writes hex bytes in memory order
+    pub fn disable_hex_reverse(mut self) -> Self {
+        self.hex_reverse = false;
+        self
+    }
+
+    /// Writes hex digits in upper case, such as "0xDEADBEEF"
+    ///
+    /// The `0x` prefix stays lowercase. Hex input is accepted in either case
+    /// regardless. A mixed-case checksum sets the case itself, so `validate`
+    /// reports enabling both.
+    pub fn enable_hex_uppercase(mut self) -> Self {
+        self.hex_uppercase = true;
+        self
+    }
+
+    /// Writes hex digits in lower case
+    pub fn disable_hex_uppercase(mut self) -> Self {
+        self.hex_uppercase = false;
+        self
+    }
+
+    /// Sets bytes format to uppercase hex with the 0x prefix, as used by Tron
+    ///
+    /// Combines `set_bytes_hex`, `enable_hex_uppercase` and
+    /// `enable_hex_prefix`. Fails with `ConfigError::UppercaseHexWithChecksum`
+    /// if a checksum such as EIP-55 is enabled, since it would decide the case.
+    pub fn set_bytes_format_hex_uppercase_prefix_combo(self) -> Result<Self, ConfigError> {
+        #[cfg(feature = "checksum")]
+        if self.hex_checksum != ChecksumAlgo::None {
+            return Err(ConfigError::UppercaseHexWithChecksum(self.hex_checksum));
+        }
+        Ok(self
+            .set_bytes_hex()
+            .enable_hex_uppercase()
+            .enable_hex_prefix())
+    }
+
+    /// Writes the 0x prefix only for hex values of the given byte lengths
+    ///
+    /// For example, `vec![20, 32]` prefixes Ethereum addresses and hashes but
+    /// leaves other data bare. This overrides `enable_hex_prefix`; both forms
+    /// are accepted on deserialize.
+    pub fn set_hex_prefix_lengths(mut self, lengths: Vec<usize>) -> Self {
+        self.hex_prefix_lengths = Some(lengths.into());
+        self
+    }
+
+    /// Writes the 0x prefix according to `enable_hex_prefix` again
+    pub fn clear_hex_prefix_lengths(mut self) -> Self {
+        self.hex_prefix_lengths = None;
+        self
+    }
+
+    /// Writes empty hex bytes as `""` instead of `"0x"`
+    ///
+    /// Non-empty bytes keep the 0x prefix. Both forms of empty bytes are
+    /// accepted on deserialize.
+    pub fn enable_hex_0x_only_if_nonempty(mut self) -> Self {
+        self.hex_0x_only_if_nonempty = true;
+        self
+    }
+
+    /// Writes the 0x prefix for empty hex bytes too
+    pub fn disable_hex_0x_only_if_nonempty(mut self) -> Self {
+        self.hex_0x_only_if_nonempty = false;
+        self
+    }
+
+    /// Sets bytes format to hexadecimal with a 0x prefix before every group
+    /// of `group_size` bytes, such as "0xaa0xbb" for a group size of 1
+    ///
+    /// This replaces the single prefix of `enable_hex_prefix`. Per-group
+    /// prefixes are stripped on deserialize. A group size of 0 is treated as 1.
+    pub fn set_bytes_hex_group_prefix(mut self, group_size: usize) -> Self {
+        self.bytes_format = BytesFormat::Hex;
+        self.hex_group_prefix = Some(group_size.max(1));
+        self
+    }
+
+    /// Writes hex bytes as a single group again
+    pub fn clear_bytes_hex_group_prefix(mut self) -> Self {
+        self.hex_group_prefix = None;
+        self
+    }
+
+    /// Sets bytes format to hexadecimal with `separator` between every group
+    /// of `group_nibbles` hex digits, such as "abcd:ef" for a group size of 4
+    ///
+    /// Groups are counted in hex digits rather than bytes, so odd sizes split
+    /// bytes. The `0x` prefix comes before the first group, and separators
+    /// are stripped on deserialize. A group size of 0 is treated as 1. The
+    /// separator must not be empty or contain hex digits, `x` or whitespace,
+    /// which `validate` reports. Ignored when a per-group `0x` prefix is set.
+    pub fn set_bytes_hex_separator(mut self, separator: &str, group_nibbles: usize) -> Self {
+        self.bytes_format = BytesFormat::Hex;
+        self.hex_separator = Some(separator.into());
+        self.hex_separator_nibbles = group_nibbles.max(1);
+        self
+    }
+
+    /// Sets bytes format to hexadecimal with `separator` between every hex
+    /// digit, such as "a-b-c-0" for `[0xab, 0xc0]`
+    pub fn set_bytes_separator_every_nibble(self, separator: &str) -> Self {
+        self.set_bytes_hex_separator(separator, 1)
+    }
+
+    /// Writes hex digits without separators
+    pub fn clear_bytes_hex_separator(mut self) -> Self {
+        self.hex_separator = None;
+        self
+    }
+
+    /// Sets a prefix such as "b64:" for string-encoded bytes
+    ///
+    /// The prefix is prepended on serialize and required on deserialize. It
+    /// is independent of the `0x` hex prefix, which follows it when enabled.
+    pub fn set_bytes_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.bytes_prefix = Some(Arc::from(prefix.into()));
+        self
+    }
+
+    /// Removes the prefix for string-encoded bytes
+    pub fn clear_bytes_prefix(mut self) -> Self {
+        self.bytes_prefix = None;
+        self
+    }
+
+    /// Escapes U+2028 and U+2029 in strings as `\u2028` and `\u2029`
+    ///
+    /// Both characters are valid in JSON but end a line in JavaScript, so
+    /// escaping them keeps output safe to embed in a script, as with JSONP.
+    /// `to_value` is not affected, since a `serde_json::Value` holds no text.
+    pub fn enable_escape_js_line_separators(mut self) -> Self {
+        self.escape_js_line_separators = true;
+        self
+    }
+
+    /// Writes U+2028 and U+2029 in strings unescaped, as serde_json does
+    pub fn disable_escape_js_line_separators(mut self) -> Self {
+        self.escape_js_line_separators = false;
+        self
+    }
+
+    /// Writes JSON5 on serialize, leaving object keys that are identifiers unquoted
+    ///
+    /// A key is written without quotes if it is an ASCII identifier: a letter,
+    /// `_` or `$` followed by letters, digits, `_` or `$`. Other keys, such as
+    /// `"my-key"` or `"1"`, keep their quotes. The output is no longer valid
+    /// JSON, and this crate does not read JSON5 back. Has no effect without the
+    /// `std` feature.
+    pub fn enable_json5_output(mut self) -> Self {
+        self.json5_output = true;
+        self
+    }
+
+    /// Writes plain JSON on serialize
+    pub fn disable_json5_output(mut self) -> Self {
+        self.json5_output = false;
+        self
+    }
+
+    /// Writes a comma after the last item of each multi-line array and object in JSON5 output
+    ///
+    /// Only applies to pretty output with `enable_json5_output`; containers
+    /// written on one line never get a trailing comma.
+    pub fn enable_json5_trailing_commas(mut self) -> Self {
+        self.json5_trailing_commas = true;
+        self
+    }
+
+    /// Writes no comma after the last item of a container
+    pub fn disable_json5_trailing_commas(mut self) -> Self {
+        self.json5_trailing_commas = false;
+        self
+    }
+
+    /// Keeps arrays with at most `max_len` scalar elements on one line when pretty-printing
+    pub fn enable_compact_arrays_inline(mut self, max_len: usize) -> Self {
+        self.compact_arrays_inline = Some(max_len);
+        self
+    }
+
+    /// Expands every array over multiple lines when pretty-printing
+    pub fn disable_compact_arrays_inline(mut self) -> Self {
+        self.compact_arrays_inline = None;
+        self
+    }
+
+    /// Pads the keys of each object to its longest key when pretty-printing
+    ///
+    /// Values of the same object then start in one column, which suits config
+    /// dumps read by people. Each object is buffered until it ends so that
+    /// its longest key is known. Widths count characters as written, so
+    /// escaped and wide characters can still misalign.
+    pub fn enable_align_object_values(mut self) -> Self {
+        self.align_object_values = true;
+        self
+    }
+
+    /// Writes a single space after each object key when pretty-printing
+    pub fn disable_align_object_values(mut self) -> Self {
+        self.align_object_values = false;
+        self
+    }
+
+    /// Writes arrays and objects compactly on one line when pretty-printing if they are short
+    ///
+    /// A container whose compact form, such as `{"x":1,"y":2}`, is at most
+    /// `max_len` bytes is written that way; longer containers, and containers
+    /// holding an expanded one, are pretty-printed as usual. Takes precedence
+    /// over `enable_compact_arrays_inline` and `enable_align_object_values`
+    /// for the containers it keeps inline.
+    pub fn set_pretty_compact_threshold(mut self, max_len: usize) -> Self {
+        self.pretty_compact_threshold = Some(max_len);
+        self
+    }
+
+    /// Pretty-prints every container regardless of its size
+    pub fn clear_pretty_compact_threshold(mut self) -> Self {
+        self.pretty_compact_threshold = None;
+        self
+    }
+
+    /// Sets the indentation used when pretty-printing
+    ///
+    /// Returns an error if `indent` contains anything other than spaces and tabs.
+    pub fn set_indent(mut self, indent: &[u8]) -> Result<Self, ConfigError> {
+        if !indent.iter().all(|b| *b == b' ' || *b == b'\t') {
+            return Err(ConfigError::InvalidIndent(indent.to_vec()));
+        }
+        self.indent = Arc::from(indent);
+        Ok(self)
+    }
+
+    /// Indents pretty-printed output with `count` tabs per level
+    pub fn set_indent_tabs(mut self, count: usize) -> Self {
+        self.indent = vec![b'\t'; count].into();
+        self
+    }
+
+    /// Accepts hexadecimal strings such as "0x1a" for integers on deserialize
+    pub fn enable_hex_numbers(mut self) -> Self {
+        self.accept_hex_numbers = true;
+        self
+    }
+
+    /// Accepts only JSON numbers for integers on deserialize
+    pub fn disable_hex_numbers(mut self) -> Self {
+        self.accept_hex_numbers = false;
+        self
+    }
+
+    /// Reads only the first value of the input on deserialize, ignoring anything after it
+    ///
+    /// `from_str("{\"a\":1} garbage", &config)` then returns `{"a":1}`. The
+    /// trailing input is not parsed, so it may be invalid JSON.
+    pub fn enable_trailing_data(mut self) -> Self {
+        self.allow_trailing_data = true;
+        self
+    }
+
+    /// Fails on deserialize if anything but whitespace follows the value
+    pub fn disable_trailing_data(mut self) -> Self {
+        self.allow_trailing_data = false;
+        self
+    }
+
+    /// Writes integers as strings with an explicit sign, such as "+5" and "-5"
+    ///
+    /// JSON numbers cannot carry a leading `+`, so signed output is written as
+    /// strings. On deserialize, integers are accepted as such strings or as
+    /// plain JSON numbers.
+    pub fn enable_force_sign(mut self) -> Self {
+        self.force_sign = true;
+        self
+    }
+
+    /// Writes integers as plain JSON numbers
+    pub fn disable_force_sign(mut self) -> Self {
+        self.force_sign = false;
+        self
+    }
+
+    /// Writes every integer and float as a string, such as `"5"` and `"1.5"`
+    ///
+    /// This avoids consumers reading integers as floats or losing precision
+    /// past 2^53. Floats keep serde_json's formatting, so `1.0` is written as
+    /// `"1.0"`; NaN and infinities are still written as `null`.
+    /// `enable_force_sign` takes precedence for integers. `to_value` produces
+    /// `Value::String` for numbers. On deserialize, integers and floats are
+    /// accepted as such strings or as plain JSON numbers.
+    pub fn enable_numbers_as_strings(mut self) -> Self {
+        self.numbers_as_strings = true;
+        self
+    }
+
+    /// Writes numbers as plain JSON numbers
+    pub fn disable_numbers_as_strings(mut self) -> Self {
+        self.numbers_as_strings = false;
+        self
+    }
+
+    /// Accepts both the standard (`+/`) and URL-safe (`-_`) base64 alphabets on deserialize
+    ///
+    /// Applies to the standard, URL-safe and MIME formats; serialization still
+    /// writes the configured alphabet. A custom engine is not affected.
+    pub fn enable_base64_accept_both_alphabets(mut self) -> Self {
+        self.base64_accept_both_alphabets = true;
+        self
+    }
+
+    /// Accepts only the configured base64 alphabet on deserialize
+    pub fn disable_base64_accept_both_alphabets(mut self) -> Self {
+        self.base64_accept_both_alphabets = false;
+        self
+    }
+
+    /// Accepts base32, z-base-32 and base36 input in either case on deserialize
+    ///
+    /// Both are case-insensitive by spec, so input is converted to the
+    /// canonical case before decoding. This never applies to base64, where
+    /// case is significant.
+    pub fn enable_bytes_case_insensitive_decode(mut self) -> Self {
+        self.bytes_case_insensitive_decode = true;
+        self
+    }
+
+    /// Accepts base32, z-base-32 and base36 input only in their canonical case on deserialize
+    pub fn disable_bytes_case_insensitive_decode(mut self) -> Self {
+        self.bytes_case_insensitive_decode = false;
+        self
+    }
+
+    /// Ignores spaces, tabs and line breaks inside hex strings on deserialize
+    ///
+    /// Useful for hex blobs stored wrapped over several lines. Whitespace is
+    /// removed after the bytes prefix, and before the `0x` prefix is checked.
+    pub fn enable_hex_ignore_whitespace(mut self) -> Self {
+        self.hex_ignore_whitespace = true;
+        self
+    }
+
+    /// Rejects whitespace inside hex strings on deserialize
+    pub fn disable_hex_ignore_whitespace(mut self) -> Self {
+        self.hex_ignore_whitespace = false;
+        self
+    }
+
+    /// Sets how `()` and unit structs are written
+    ///
+    /// Only serialization is affected; deserializing a unit still expects `null`.
+    pub fn set_unit_as(mut self, unit_as: UnitRepr) -> Self {
+        self.unit_as = unit_as;
+        self
+    }
+
+    /// Sets how `Result` fields using `#[serde(with = "serde_json_ext::result")]` are written and read
+    ///
+    /// Plain `Result` fields keep serde's tagged representation.
+    pub fn set_result_repr(mut self, result_repr: ResultRepr) -> Self {
+        self.result_repr = result_repr;
+        self
+    }
+
+    /// Sets how subnormal `f32` and `f64` values are written
+    ///
+    /// Only serialization is affected.
+    pub fn set_subnormal_policy(mut self, policy: SubnormalPolicy) -> Self {
+        self.subnormal_policy = policy;
+        self
+    }
+
+    /// Writes negative zero `f32` and `f64` values as the integer `0`, as canonical JSON does
+    ///
+    /// Other floats, including positive zero, are unchanged. Subnormals
+    /// flushed to `-0.0` by `SubnormalPolicy::Zero` are normalized too.
+    pub fn enable_normalize_negative_zero(mut self) -> Self {
+        self.normalize_negative_zero = true;
+        self
+    }
+
+    /// Writes negative zero as `-0.0`, as serde_json does
+    pub fn disable_normalize_negative_zero(mut self) -> Self {
+        self.normalize_negative_zero = false;
+        self
+    }
+
+    /// Limits the nesting depth of arrays, objects and enum variants on deserialize
+    ///
+    /// The limit is checked before the wrapper descends into a container, so
+    /// deeply nested input fails with an error instead of overflowing the stack.
+    /// When parsing text, serde_json also enforces its own recursion limit of
+    /// 128 levels; a `max_depth` above that only takes effect for `from_value`.
+    pub fn set_max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Limits the length in bytes of strings read on deserialize
+    ///
+    /// Applies to string fields and to the string input of string-based
+    /// bytes formats such as hex and base64, measured as written (including
+    /// any bytes prefix). Object keys are not checked.
+    pub fn set_max_string_len(mut self, max_string_len: Option<usize>) -> Self {
+        self.max_string_len = max_string_len;
+        self
+    }
+
+    /// Limits the number of bytes `from_reader` reads from its reader
+    ///
+    /// Reading past the limit fails with `Error::InputTooLarge`. Slices and
+    /// strings are already in memory, so the other entry points ignore it.
+    pub fn set_max_input_bytes(mut self, max_input_bytes: Option<usize>) -> Self {
+        self.max_input_bytes = max_input_bytes;
+        self
+    }
+
+    /// Checks the config for contradictory options
+    ///
+    /// Returns the first conflict found, in this order:
+    ///
+    /// | Options | Error |
+    /// |---|---|
+    /// | `Base64Custom` format without an engine | `MissingBase64Engine` |
+    /// | hex checksum enabled, format other than `Hex` | `ChecksumRequiresHex` |
+    /// | bytes prefix set, format not always written as a string, such as `HexOrInteger` | `BytesPrefixRequiresStringFormat` |
+    /// | uppercase hex with a checksum | `UppercaseHexWithChecksum` |
+    /// | hex separator empty or with hex digits, `x` or whitespace | `InvalidHexSeparator` |
+    /// | data URI MIME type with `,` | `InvalidDataUriMimeType` |
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.bytes_format == BytesFormat::Base64Custom && self.base64_engine.is_none() {
+            return Err(ConfigError::MissingBase64Engine);
+        }
+        #[cfg(feature = "checksum")]
+        if self.hex_checksum != ChecksumAlgo::None && self.bytes_format != BytesFormat::Hex {
+            return Err(ConfigError::ChecksumRequiresHex(self.bytes_format));
+        }
+        if self.bytes_prefix.is_some() && !self.bytes_format.is_string() {
+            return Err(ConfigError::BytesPrefixRequiresStringFormat);
+        }
+        #[cfg(feature = "checksum")]
+        if self.hex_uppercase && self.hex_checksum != ChecksumAlgo::None {
+            return Err(ConfigError::UppercaseHexWithChecksum(self.hex_checksum));
+        }
+        if let Some(separator) = &self.hex_separator
+            && (separator.is_empty()
+                || separator.contains(|c: char| {
+                    // `x` would be read as part of a `0x` prefix, and
+                    // whitespace may be ignored before separators are stripped
+                    c.is_ascii_hexdigit() || c.eq_ignore_ascii_case(&'x') || c.is_whitespace()
+                }))
+        {
+            return Err(ConfigError::InvalidHexSeparator(String::from(&**separator)));
+        }
+        if self.data_uri_mime_type.contains(',') {
+            return Err(ConfigError::InvalidDataUriMimeType(String::from(
+                &*self.data_uri_mime_type,
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Returns the bytes encoding format
+    pub fn bytes_format(&self) -> BytesFormat {
+        self.bytes_format
+    }
+
+    /// Returns whether EIP-55 checksum encoding is enabled
+    #[cfg(feature = "checksum")]
+    pub fn hex_eip55(&self) -> bool {
+        self.hex_checksum == ChecksumAlgo::Eip55
+    }
+
+    /// Returns the checksum applied to hex output
+    #[cfg(feature = "checksum")]
+    pub fn hex_checksum(&self) -> ChecksumAlgo {
+        self.hex_checksum
+    }
+
+    /// Returns whether hex values carry a 0x prefix
+    pub fn hex_prefix(&self) -> bool {
+        self.hex_prefix
+    }
+
+    /// Returns whether U+2028 and U+2029 are escaped in strings
+    pub fn escape_js_line_separators(&self) -> bool {
+        self.escape_js_line_separators
+    }
+
+    /// Returns whether identifier keys are written unquoted as JSON5
+    pub fn json5_output(&self) -> bool {
+        self.json5_output
+    }
+
+    /// Returns whether expanded containers get a trailing comma in JSON5 output
+    pub fn json5_trailing_commas(&self) -> bool {
+        self.json5_trailing_commas
+    }
+
+    /// Returns whether hex bytes are written in reverse order
+    pub fn hex_reverse(&self) -> bool {
+        self.hex_reverse
+    }
+
+    /// Returns whether hex digits are written in upper case
+    pub fn hex_uppercase(&self) -> bool {
+        self.hex_uppercase
+    }
+
+    /// Returns the byte lengths that get a 0x prefix in hex output, if set
+    pub fn hex_prefix_lengths(&self) -> Option<&[usize]> {
+        self.hex_prefix_lengths.as_deref()
+    }
+
+    /// Returns whether empty hex bytes are written without the 0x prefix
+    pub fn hex_0x_only_if_nonempty(&self) -> bool {
+        self.hex_0x_only_if_nonempty
+    }
+
+    /// Returns the group size used for per-group 0x prefixes, if set
+    pub fn hex_group_prefix(&self) -> Option<usize> {
+        self.hex_group_prefix
+    }
+
+    /// Returns the hex separator and the number of hex digits per group, if set
+    pub fn hex_separator(&self) -> Option<(&str, usize)> {
+        self.hex_separator
+            .as_deref()
+            .map(|separator| (separator, self.hex_separator_nibbles))
+    }
+
+    /// Returns the prefix for string-encoded bytes, if any
+    pub fn bytes_prefix(&self) -> Option<&str> {
+        self.bytes_prefix.as_deref()
+    }
+
+    /// Returns the maximum length of arrays kept on one line when pretty-printing
+    pub fn compact_arrays_inline(&self) -> Option<usize> {
+        self.compact_arrays_inline
+    }
+
+    /// Returns whether object values are aligned when pretty-printing
+    pub fn align_object_values(&self) -> bool {
+        self.align_object_values
+    }
+
+    /// Returns the compact length up to which containers stay on one line when pretty-printing
+    pub fn pretty_compact_threshold(&self) -> Option<usize> {
+        self.pretty_compact_threshold
+    }
+
+    /// Returns the indentation used when pretty-printing
+    pub fn indent(&self) -> &[u8] {
+        &self.indent
+    }
+
+    /// Returns the engine used by `BytesFormat::Base64Custom`, if any
+    pub fn base64_engine(&self) -> Option<&Base64Engine> {
+        self.base64_engine.as_ref()
+    }
+
+    /// Returns the codec of a `BytesFormat::Codec` bytes format, if any
+    pub fn bytes_codec(&self) -> Option<&'static dyn ByteCodec> {
+        match self.bytes_format {
+            BytesFormat::Codec(codec) => Some(codec),
+            _ => None,
+        }
+    }
+
+    /// Returns whether hexadecimal strings are accepted for integers
+    pub fn hex_numbers(&self) -> bool {
+        self.accept_hex_numbers
+    }
+
+    /// Returns whether input after the first value is ignored on deserialize
+    pub fn allow_trailing_data(&self) -> bool {
+        self.allow_trailing_data
+    }
+
+    /// Returns the maximum nesting depth on deserialize, if any
+    pub fn max_depth(&self) -> Option<usize> {
+        self.max_depth
+    }
+
+    /// Returns the maximum string length on deserialize, if any
+    pub fn max_string_len(&self) -> Option<usize> {
+        self.max_string_len
+    }
+
+    /// Returns the maximum number of bytes read by `from_reader`, if any
+    pub fn max_input_bytes(&self) -> Option<usize> {
+        self.max_input_bytes
+    }
+
+    /// Returns whether base64 decoding accepts both alphabets
+    pub fn base64_accept_both_alphabets(&self) -> bool {
+        self.base64_accept_both_alphabets
+    }
+
+    /// Returns whether base64 input must be padded exactly as written
+    pub fn base64_strict_padding(&self) -> bool {
+        self.base64_strict_padding
+    }
+
+    /// Returns whether base64 input is rejected early for an invalid length
+    pub fn validate_base64_length(&self) -> bool {
+        self.validate_base64_length
+    }
+
+    /// Returns whether 0x-prefixed hex `Display` output is written as bytes
+    pub fn display_hex_as_bytes(&self) -> bool {
+        self.display_hex_as_bytes
+    }
+
+    /// Returns whether base32 input is accepted in either case
+    pub fn bytes_case_insensitive_decode(&self) -> bool {
+        self.bytes_case_insensitive_decode
+    }
+
+    /// Returns whether whitespace inside hex strings is ignored
+    pub fn hex_ignore_whitespace(&self) -> bool {
+        self.hex_ignore_whitespace
+    }
+
+    /// Returns whether integers are written with an explicit sign
+    pub fn force_sign(&self) -> bool {
+        self.force_sign
+    }
+
+    /// Returns whether every number is written as a string
+    pub fn numbers_as_strings(&self) -> bool {
+        self.numbers_as_strings
+    }
+
+    /// Returns whether default-format bytes are written as signed values
+    pub fn bytes_default_signed(&self) -> bool {
+        self.bytes_default_signed
+    }
+
+    /// Returns the radix of default-format bytes elements, if they are written as strings
+    pub fn bytes_array_radix(&self) -> Option<BytesArrayRadix> {
+        self.bytes_array_radix
+    }
+
+    /// Returns the bytes format used for empty bytes, if set
+    pub fn bytes_empty_format(&self) -> Option<BytesFormat> {
+        self.bytes_empty_format
+    }
+
+    /// Returns the string written in place of empty bytes, if set
+    pub fn bytes_empty_placeholder(&self) -> Option<&str> {
+        self.bytes_empty_placeholder.as_deref()
+    }
+
+    /// Returns the bytes formats tried in turn on deserialize, if set
+    pub fn bytes_decode_order(&self) -> Option<&[BytesFormat]> {
+        self.bytes_decode_order.as_deref()
+    }
+
+    /// Returns the bytes format registered for newtype structs named `name`, if any
+    pub fn bytes_format_for_type_name(&self, name: &str) -> Option<BytesFormat> {
+        self.bytes_type_formats
+            .as_deref()?
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, format)| *format)
+    }
+
+    /// Returns the bytes format registered for bytes at nesting `depth`, if any
+    ///
+    /// See [`Config::set_bytes_format_for_depth`].
+    pub fn bytes_format_for_depth(&self, depth: usize) -> Option<BytesFormat> {
+        self.bytes_depth_formats
+            .as_deref()?
+            .iter()
+            .take_while(|(d, _)| *d <= depth)
+            .last()
+            .map(|(_, format)| *format)
+    }
+
+    /// Returns how `()` and unit structs are written
+    pub fn unit_as(&self) -> UnitRepr {
+        self.unit_as
+    }
+
+    /// Returns how `Result` fields using the `result` module are represented
+    pub fn result_repr(&self) -> ResultRepr {
+        self.result_repr
+    }
+
+    /// Returns whether a CRC-32 suffix is appended to bytes
+    pub fn bytes_checksum_suffix(&self) -> bool {
+        self.bytes_checksum_suffix
+    }
+
+    /// Returns how subnormal floats are written
+    pub fn subnormal_policy(&self) -> SubnormalPolicy {
+        self.subnormal_policy
+    }
+
+    /// Returns the MIME type written by `BytesFormat::DataUri`
+    pub fn data_uri_mime_type(&self) -> &str {
+        &self.data_uri_mime_type
+    }
+
+    /// Returns whether negative zero is written as `0`
+    pub fn normalize_negative_zero(&self) -> bool {
+        self.normalize_negative_zero
+    }
+}
+
+impl Config {
+    /// Whether hex output of `len` bytes gets a 0x prefix
+    pub(crate) fn hex_prefix_for(&self, len: usize) -> bool {
+        if len == 0 && self.hex_0x_only_if_nonempty {
+            return false;
+        }
+        match &self.hex_prefix_lengths {
+            Some(lengths) => lengths.contains(&len),
+            None => self.hex_prefix,
+        }
+    }
+
+    /// Whether integers may be deserialized from strings
+    pub(crate) fn accepts_number_strings(&self) -> bool {
+        self.accept_hex_numbers || self.force_sign || self.numbers_as_strings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_set_indent() {
+        let config = Config::default().set_indent(b" \t ").unwrap();
+        assert_eq!(config.indent(), b" \t ");
+
+        let config = Config::default().set_indent_tabs(2);
+        assert_eq!(config.indent(), b"\t\t");
+
+        let result = Config::default().set_indent(b"--");
+        assert_eq!(
+            result.unwrap_err(),
+            ConfigError::InvalidIndent(b"--".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_clone_with_bytes_format() {
+        let base = Config::default().enable_hex_prefix().set_indent_tabs(1);
+
+        let hex = base.clone_with_bytes_format(BytesFormat::Hex);
+        assert_eq!(hex.bytes_format, BytesFormat::Hex);
+        assert!(hex.hex_prefix);
+        assert_eq!(hex.indent(), b"\t");
+
+        let base64 = base.clone_with_bytes_format(BytesFormat::Base64(Base64Variant::STANDARD));
+        assert_eq!(
+            base64.bytes_format,
+            BytesFormat::Base64(Base64Variant::STANDARD)
+        );
+        assert!(base64.hex_prefix);
+        assert_eq!(base.bytes_format, BytesFormat::Default);
+
+        let bytes = serde_bytes::Bytes::new(&[1, 2, 255]);
+        assert_eq!(crate::to_string(&bytes, &hex).unwrap(), r#""0x0102ff""#);
+        assert_eq!(crate::to_string(&bytes, &base64).unwrap(), r#""AQL/""#);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(Config::default().validate(), Ok(()));
+
+        let config = Config::default().clone_with_bytes_format(BytesFormat::Base64Custom);
+        assert_eq!(config.validate(), Err(ConfigError::MissingBase64Engine));
+
+        let config = Config::default().set_bytes_prefix("b64:");
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::BytesPrefixRequiresStringFormat)
+        );
+
+        // Short bytes are written as integers, which cannot carry the prefix
+        let config = Config::default()
+            .set_bytes_hex_or_integer()
+            .set_bytes_prefix("hex:");
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::BytesPrefixRequiresStringFormat)
+        );
+
+        let config = Config::default().set_bytes_data_uri("text/plain;a=b,c");
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::InvalidDataUriMimeType(
+                "text/plain;a=b,c".into()
+            ))
+        );
+        let config = Config::default().set_bytes_data_uri("text/plain;charset=utf-8");
+        assert_eq!(config.validate(), Ok(()));
+
+        for separator in ["", "a", "-0", "x", "-X-", " ", ":\n"] {
+            let config = Config::default().set_bytes_hex_separator(separator, 2);
+            assert_eq!(
+                config.validate(),
+                Err(ConfigError::InvalidHexSeparator(separator.into()))
+            );
+        }
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_validate_checksum() {
+        let config = Config::default().set_bytes_hex().enable_hex_eip55();
+        assert!(config.hex_eip55());
+        assert_eq!(config.validate(), Ok(()));
+
+        let config = Config::default().set_bytes_base64().enable_hex_eip55();
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::ChecksumRequiresHex(BytesFormat::Base64(
+                Base64Variant::STANDARD
+            )))
+        );
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_uppercase()
+            .enable_hex_eip55();
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::UppercaseHexWithChecksum(ChecksumAlgo::Eip55))
+        );
+
+        let err = Config::default()
+            .enable_hex_eip55()
+            .set_bytes_format_hex_uppercase_prefix_combo()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            ConfigError::UppercaseHexWithChecksum(ChecksumAlgo::Eip55)
+        );
+
+        // Enabling a checksum afterwards is caught by validate
+        let config = Config::default()
+            .set_bytes_format_hex_uppercase_prefix_combo()
+            .unwrap()
+            .enable_hex_eip55();
+        assert_eq!(
+            config.validate(),
+            Err(ConfigError::UppercaseHexWithChecksum(ChecksumAlgo::Eip55))
+        );
+    }
+
+    #[test]
+    fn test_set_bytes_format_hex_uppercase_prefix_combo() {
+        let config = Config::default()
+            .set_bytes_format_hex_uppercase_prefix_combo()
+            .unwrap();
+        assert_eq!(config.bytes_format(), BytesFormat::Hex);
+        assert!(config.hex_uppercase());
+        assert!(config.hex_prefix());
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_getters() {
+        let config = Config::default();
+        assert_eq!(config.bytes_format(), BytesFormat::Default);
+        assert!(!config.hex_prefix());
+        assert_eq!(config.bytes_prefix(), None);
+        assert_eq!(config.indent(), b"  ");
+        assert!(config.base64_engine().is_none());
+        assert_eq!(config.max_depth(), None);
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_bytes_prefix("hex:")
+            .enable_compact_arrays_inline(4)
+            .set_indent_tabs(1)
+            .enable_hex_numbers()
+            .set_max_depth(Some(16))
+            .enable_bytes_case_insensitive_decode()
+            .enable_force_sign()
+            .enable_bytes_default_signed();
+        assert_eq!(config.bytes_format(), BytesFormat::Hex);
+        assert!(config.hex_prefix());
+        assert_eq!(config.bytes_prefix(), Some("hex:"));
+        assert_eq!(config.compact_arrays_inline(), Some(4));
+        assert_eq!(config.indent(), b"\t");
+        assert!(config.hex_numbers());
+        assert_eq!(config.max_depth(), Some(16));
+        assert!(config.bytes_case_insensitive_decode());
+        assert!(config.force_sign());
+        assert!(config.bytes_default_signed());
+
+        let config = Config::default()
+            .set_bytes_base64_custom_engine(base64::engine::general_purpose::STANDARD);
+        assert!(config.base64_engine().is_some());
+    }
+
+    #[test]
+    fn test_clone_shares_heavy_fields() {
+        use base64::{alphabet::Alphabet, engine::GeneralPurpose, engine::general_purpose::PAD};
+
+        let alphabet =
+            Alphabet::new("ZYXWVUTSRQPONMLKJIHGFEDCBAzyxwvutsrqponmlkjihgfedcba9876543210+/")
+                .unwrap();
+        let config = Config::default()
+            .set_bytes_base64_custom_engine(GeneralPurpose::new(&alphabet, PAD))
+            .set_bytes_prefix("b64:")
+            .set_indent_tabs(64)
+            .set_hex_prefix_lengths(vec![20, 32])
+            .set_bytes_decode_order(vec![BytesFormat::Base64Custom, BytesFormat::Hex]);
+
+        let clone = config.clone().enable_hex_prefix();
+        assert!(Arc::ptr_eq(
+            &config.base64_engine.as_ref().unwrap().0,
+            &clone.base64_engine.as_ref().unwrap().0
+        ));
+        assert!(Arc::ptr_eq(&config.indent, &clone.indent));
+        assert!(Arc::ptr_eq(
+            config.bytes_prefix.as_ref().unwrap(),
+            clone.bytes_prefix.as_ref().unwrap()
+        ));
+        assert!(Arc::ptr_eq(
+            config.hex_prefix_lengths.as_ref().unwrap(),
+            clone.hex_prefix_lengths.as_ref().unwrap()
+        ));
+        assert!(Arc::ptr_eq(
+            config.bytes_decode_order.as_ref().unwrap(),
+            clone.bytes_decode_order.as_ref().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_default_trait() {
+        let configs = [Some(Config::default().set_bytes_hex()), None];
+        let formats: Vec<BytesFormat> = configs
+            .into_iter()
+            .map(|config| config.unwrap_or_default().bytes_format())
+            .collect();
+        assert_eq!(formats, [BytesFormat::Hex, BytesFormat::Default]);
+
+        #[derive(Default)]
+        struct Settings {
+            json: Config,
+        }
+
+        let settings = Settings::default();
+        assert_eq!(
+            settings.json.indent(),
+            <Config as Default>::default().indent()
+        );
+    }
+
+    #[test]
+    fn test_set_bytes_format_name() {
+        let cases = [
+            ("array", BytesFormat::Default, false),
+            ("hex", BytesFormat::Hex, false),
+            ("hex0x", BytesFormat::Hex, true),
+            (
+                "base64",
+                BytesFormat::Base64(Base64Variant::STANDARD),
+                false,
+            ),
+            (
+                "base64url",
+                BytesFormat::Base64(Base64Variant::URL_SAFE),
+                false,
+            ),
+            ("base64mime", BytesFormat::Base64Mime, false),
+            ("base64crypt", BytesFormat::Base64Crypt, false),
+            ("base32", BytesFormat::Base32, false),
+            ("zbase32", BytesFormat::ZBase32, false),
+            ("base36", BytesFormat::Base36, false),
+            ("uuencode", BytesFormat::UuEncode, false),
+            ("rlp", BytesFormat::Rlp, false),
+            ("protobuf", BytesFormat::Protobuf, false),
+            ("utf8", BytesFormat::Utf8, false),
+        ];
+        assert_eq!(cases.len(), BYTES_FORMAT_NAMES.len());
+
+        for (name, format, prefix) in cases {
+            let config = Config::default()
+                .enable_hex_prefix()
+                .set_bytes_format_name(name)
+                .unwrap();
+            assert_eq!(config.bytes_format(), format, "{}", name);
+            if format == BytesFormat::Hex {
+                assert_eq!(config.hex_prefix(), prefix, "{}", name);
+            }
+        }
+
+        let err = Config::default()
+            .set_bytes_format_name("base58")
+            .unwrap_err();
+        assert_eq!(err, ConfigError::UnknownBytesFormatName("base58".into()));
+        assert_eq!(
+            err.to_string(),
+            "unknown bytes format \"base58\", expected one of: array, hex, hex0x, base64, \
+             base64url, base64mime, base64crypt, base32, zbase32, base36, uuencode, rlp, \
+             protobuf, utf8"
+        );
+    }
 }
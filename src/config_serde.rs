@@ -0,0 +1,440 @@
+// Serialize and Deserialize for Config, for storing it in settings files
+
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "checksum")]
+use crate::ChecksumAlgo;
+use crate::{
+    Base64Variant, BytesArrayRadix, BytesFormat, Config, ResultRepr, SubnormalPolicy, UnitRepr,
+};
+
+impl BytesFormat {
+    /// Name of the format in a serialized `Config`
+    ///
+    /// Returns `None` for formats that need a value set in code.
+    #[allow(deprecated)]
+    pub(crate) fn settings_name(self) -> Option<&'static str> {
+        Some(match self {
+            BytesFormat::Default => "array",
+            BytesFormat::Hex => "hex",
+            BytesFormat::Base64(Base64Variant::STANDARD) => "base64",
+            BytesFormat::Base64(Base64Variant::STANDARD_NO_PAD) => "base64nopad",
+            BytesFormat::Base64(Base64Variant::URL_SAFE) | BytesFormat::Base64UrlSafe => {
+                "base64url"
+            }
+            BytesFormat::Base64(Base64Variant::URL_SAFE_NO_PAD) => "base64urlnopad",
+            BytesFormat::Base64Mime => "base64mime",
+            BytesFormat::Base64Crypt => "base64crypt",
+            BytesFormat::Rlp => "rlp",
+            BytesFormat::Protobuf => "protobuf",
+            BytesFormat::Base32 => "base32",
+            BytesFormat::ZBase32 => "zbase32",
+            BytesFormat::Base36 => "base36",
+            BytesFormat::DataUri => "data_uri",
+            BytesFormat::UuEncode => "uuencode",
+            BytesFormat::MongoExtended => "mongo_extended",
+            BytesFormat::LengthPrefixed => "length_prefixed",
+            BytesFormat::NodeBuffer => "node_buffer",
+            BytesFormat::HexOrInteger => "hex_or_integer",
+            BytesFormat::HexAndLen => "hex_and_len",
+            BytesFormat::Utf8 => "utf8",
+            BytesFormat::HexOrBase64 => "hex_or_base64",
+            BytesFormat::Base64Custom | BytesFormat::Codec(_) => return None,
+        })
+    }
+
+    pub(crate) fn from_settings_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "array" => BytesFormat::Default,
+            "hex" => BytesFormat::Hex,
+            "base64" => BytesFormat::Base64(Base64Variant::STANDARD),
+            "base64nopad" => BytesFormat::Base64(Base64Variant::STANDARD_NO_PAD),
+            "base64url" => BytesFormat::Base64(Base64Variant::URL_SAFE),
+            "base64urlnopad" => BytesFormat::Base64(Base64Variant::URL_SAFE_NO_PAD),
+            "base64mime" => BytesFormat::Base64Mime,
+            "base64crypt" => BytesFormat::Base64Crypt,
+            "rlp" => BytesFormat::Rlp,
+            "protobuf" => BytesFormat::Protobuf,
+            "base32" => BytesFormat::Base32,
+            "zbase32" => BytesFormat::ZBase32,
+            "base36" => BytesFormat::Base36,
+            "data_uri" => BytesFormat::DataUri,
+            "uuencode" => BytesFormat::UuEncode,
+            "mongo_extended" => BytesFormat::MongoExtended,
+            "length_prefixed" => BytesFormat::LengthPrefixed,
+            "node_buffer" => BytesFormat::NodeBuffer,
+            "hex_or_integer" => BytesFormat::HexOrInteger,
+            "hex_and_len" => BytesFormat::HexAndLen,
+            "utf8" => BytesFormat::Utf8,
+            "hex_or_base64" => BytesFormat::HexOrBase64,
+            _ => return None,
+        })
+    }
+
+    /// Name of the format in error messages, its settings name where it has one
+    pub(crate) fn error_name(self) -> &'static str {
+        match self {
+            BytesFormat::Base64Custom => "base64_custom",
+            BytesFormat::Codec(_) => "codec",
+            format => format.settings_name().unwrap_or("unknown"),
+        }
+    }
+}
+
+fn format_name<E: serde::ser::Error>(format: BytesFormat) -> Result<String, E> {
+    format.settings_name().map(String::from).ok_or_else(|| {
+        E::custom(format_args!(
+            "bytes format {:?} needs a value set in code and cannot be serialized",
+            format
+        ))
+    })
+}
+
+fn format_from_name<E: serde::de::Error>(name: &str) -> Result<BytesFormat, E> {
+    BytesFormat::from_settings_name(name)
+        .ok_or_else(|| E::custom(format_args!("unknown bytes format {:?}", name)))
+}
+
+/// The serializable fields of a `Config`
+///
+/// Custom base64 engines, byte codecs and bytes transforms are not part of it.
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+struct ConfigRepr {
+    bytes_format: String,
+    #[cfg(feature = "checksum")]
+    hex_checksum: ChecksumAlgo,
+    hex_prefix: bool,
+    hex_reverse: bool,
+    hex_uppercase: bool,
+    hex_prefix_lengths: Option<Vec<usize>>,
+    hex_0x_only_if_nonempty: bool,
+    hex_group_prefix: Option<usize>,
+    hex_separator: Option<String>,
+    hex_separator_nibbles: usize,
+    hex_ignore_whitespace: bool,
+    bytes_prefix: Option<String>,
+    display_hex_as_bytes: bool,
+    escape_js_line_separators: bool,
+    json5_output: bool,
+    json5_trailing_commas: bool,
+    compact_arrays_inline: Option<usize>,
+    align_object_values: bool,
+    pretty_compact_threshold: Option<usize>,
+    indent: String,
+    data_uri_mime_type: String,
+    accept_hex_numbers: bool,
+    allow_trailing_data: bool,
+    max_depth: Option<usize>,
+    max_string_len: Option<usize>,
+    max_input_bytes: Option<usize>,
+    base64_accept_both_alphabets: bool,
+    base64_strict_padding: bool,
+    validate_base64_length: bool,
+    bytes_case_insensitive_decode: bool,
+    force_sign: bool,
+    numbers_as_strings: bool,
+    bytes_default_signed: bool,
+    bytes_array_radix: Option<BytesArrayRadix>,
+    bytes_checksum_suffix: bool,
+    bytes_empty_format: Option<String>,
+    bytes_empty_placeholder: Option<String>,
+    bytes_decode_order: Option<Vec<String>>,
+    bytes_type_formats: Option<BTreeMap<String, String>>,
+    bytes_depth_formats: Option<BTreeMap<usize, String>>,
+    unit_as: UnitRepr,
+    result_repr: ResultRepr,
+    subnormal_policy: SubnormalPolicy,
+    normalize_negative_zero: bool,
+}
+
+impl Default for ConfigRepr {
+    fn default() -> Self {
+        // The default config has no engine or codec, so every field converts
+        ConfigRepr::from_config::<serde_json::Error>(&Config::default()).unwrap()
+    }
+}
+
+impl ConfigRepr {
+    fn from_config<E: serde::ser::Error>(config: &Config) -> Result<Self, E> {
+        Ok(ConfigRepr {
+            bytes_format: format_name(config.bytes_format)?,
+            #[cfg(feature = "checksum")]
+            hex_checksum: config.hex_checksum,
+            hex_prefix: config.hex_prefix,
+            hex_reverse: config.hex_reverse,
+            hex_uppercase: config.hex_uppercase,
+            hex_prefix_lengths: config.hex_prefix_lengths.as_deref().map(<[usize]>::to_vec),
+            hex_0x_only_if_nonempty: config.hex_0x_only_if_nonempty,
+            hex_group_prefix: config.hex_group_prefix,
+            hex_separator: config.hex_separator.as_deref().map(String::from),
+            hex_separator_nibbles: config.hex_separator_nibbles,
+            hex_ignore_whitespace: config.hex_ignore_whitespace,
+            bytes_prefix: config.bytes_prefix.as_deref().map(String::from),
+            display_hex_as_bytes: config.display_hex_as_bytes,
+            escape_js_line_separators: config.escape_js_line_separators,
+            json5_output: config.json5_output,
+            json5_trailing_commas: config.json5_trailing_commas,
+            compact_arrays_inline: config.compact_arrays_inline,
+            align_object_values: config.align_object_values,
+            pretty_compact_threshold: config.pretty_compact_threshold,
+            // Indentation only ever holds spaces and tabs
+            indent: String::from_utf8_lossy(&config.indent).into_owned(),
+            data_uri_mime_type: config.data_uri_mime_type.as_ref().into(),
+            accept_hex_numbers: config.accept_hex_numbers,
+            allow_trailing_data: config.allow_trailing_data,
+            max_depth: config.max_depth,
+            max_string_len: config.max_string_len,
+            max_input_bytes: config.max_input_bytes,
+            base64_accept_both_alphabets: config.base64_accept_both_alphabets,
+            base64_strict_padding: config.base64_strict_padding,
+            validate_base64_length: config.validate_base64_length,
+            bytes_case_insensitive_decode: config.bytes_case_insensitive_decode,
+            force_sign: config.force_sign,
+            numbers_as_strings: config.numbers_as_strings,
+            bytes_default_signed: config.bytes_default_signed,
+            bytes_array_radix: config.bytes_array_radix,
+            bytes_checksum_suffix: config.bytes_checksum_suffix,
+            bytes_empty_format: config.bytes_empty_format.map(format_name).transpose()?,
+            bytes_empty_placeholder: config.bytes_empty_placeholder.as_deref().map(Into::into),
+            bytes_decode_order: config
+                .bytes_decode_order
+                .as_deref()
+                .map(|order| order.iter().copied().map(format_name).collect())
+                .transpose()?,
+            bytes_type_formats: config
+                .bytes_type_formats
+                .as_deref()
+                .map(|formats| {
+                    formats
+                        .iter()
+                        .map(|(name, format)| Ok((name.clone(), format_name(*format)?)))
+                        .collect()
+                })
+                .transpose()?,
+            bytes_depth_formats: config
+                .bytes_depth_formats
+                .as_deref()
+                .map(|formats| {
+                    formats
+                        .iter()
+                        .map(|(depth, format)| Ok((*depth, format_name(*format)?)))
+                        .collect()
+                })
+                .transpose()?,
+            unit_as: config.unit_as,
+            result_repr: config.result_repr,
+            subnormal_policy: config.subnormal_policy,
+            normalize_negative_zero: config.normalize_negative_zero,
+        })
+    }
+
+    fn into_config<E: serde::de::Error>(self) -> Result<Config, E> {
+        let config = Config::default()
+            .set_indent(self.indent.as_bytes())
+            .map_err(E::custom)?;
+        Ok(Config {
+            bytes_format: format_from_name(&self.bytes_format)?,
+            data_uri_mime_type: self.data_uri_mime_type.into(),
+            #[cfg(feature = "checksum")]
+            hex_checksum: self.hex_checksum,
+            hex_prefix: self.hex_prefix,
+            hex_reverse: self.hex_reverse,
+            hex_uppercase: self.hex_uppercase,
+            hex_prefix_lengths: self.hex_prefix_lengths.map(Arc::from),
+            hex_0x_only_if_nonempty: self.hex_0x_only_if_nonempty,
+            hex_group_prefix: self.hex_group_prefix.map(|size| size.max(1)),
+            hex_separator: self.hex_separator.map(Arc::from),
+            hex_separator_nibbles: self.hex_separator_nibbles.max(1),
+            hex_ignore_whitespace: self.hex_ignore_whitespace,
+            bytes_prefix: self.bytes_prefix.map(Arc::from),
+            display_hex_as_bytes: self.display_hex_as_bytes,
+            escape_js_line_separators: self.escape_js_line_separators,
+            json5_output: self.json5_output,
+            json5_trailing_commas: self.json5_trailing_commas,
+            compact_arrays_inline: self.compact_arrays_inline,
+            align_object_values: self.align_object_values,
+            pretty_compact_threshold: self.pretty_compact_threshold,
+            accept_hex_numbers: self.accept_hex_numbers,
+            allow_trailing_data: self.allow_trailing_data,
+            max_depth: self.max_depth,
+            max_string_len: self.max_string_len,
+            max_input_bytes: self.max_input_bytes,
+            base64_accept_both_alphabets: self.base64_accept_both_alphabets,
+            base64_strict_padding: self.base64_strict_padding,
+            validate_base64_length: self.validate_base64_length,
+            bytes_case_insensitive_decode: self.bytes_case_insensitive_decode,
+            force_sign: self.force_sign,
+            numbers_as_strings: self.numbers_as_strings,
+            bytes_default_signed: self.bytes_default_signed,
+            bytes_array_radix: self.bytes_array_radix,
+            bytes_checksum_suffix: self.bytes_checksum_suffix,
+            bytes_empty_format: self
+                .bytes_empty_format
+                .as_deref()
+                .map(format_from_name)
+                .transpose()?,
+            bytes_empty_placeholder: self.bytes_empty_placeholder.map(Arc::from),
+            bytes_decode_order: self
+                .bytes_decode_order
+                .map(|order| {
+                    order
+                        .iter()
+                        .map(|name| format_from_name(name))
+                        .collect::<Result<Vec<_>, E>>()
+                })
+                .transpose()?
+                .map(Arc::from),
+            bytes_type_formats: self
+                .bytes_type_formats
+                .map(|formats| {
+                    formats
+                        .into_iter()
+                        .map(|(name, format)| Ok((name, format_from_name(&format)?)))
+                        .collect::<Result<Vec<_>, E>>()
+                })
+                .transpose()?
+                .map(Arc::from),
+            // A `BTreeMap` iterates in depth order
+            bytes_depth_formats: self
+                .bytes_depth_formats
+                .map(|formats| {
+                    formats
+                        .into_iter()
+                        .map(|(depth, format)| Ok((depth, format_from_name(&format)?)))
+                        .collect::<Result<Vec<_>, E>>()
+                })
+                .transpose()?
+                .map(Arc::from),
+            unit_as: self.unit_as,
+            result_repr: self.result_repr,
+            subnormal_policy: self.subnormal_policy,
+            normalize_negative_zero: self.normalize_negative_zero,
+            ..config
+        })
+    }
+}
+
+/// Writes the options that can be stored in a settings file
+///
+/// Bytes formats are written by name, such as `"hex"` or `"base64url"`.
+/// Custom base64 engines, byte codecs and bytes transforms are skipped, and
+/// a config whose bytes format needs one of them fails to serialize.
+impl Serialize for Config {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ConfigRepr::from_config(self)?.serialize(serializer)
+    }
+}
+
+/// Reads options written by `Serialize`; absent options keep their defaults
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        ConfigRepr::deserialize(deserializer)?.into_config()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_hex_prefix_lengths(vec![20, 32])
+            .set_bytes_prefix("hex:")
+            .set_indent_tabs(1)
+            .set_max_depth(Some(16))
+            .set_unit_as(UnitRepr::EmptyObject)
+            .set_subnormal_policy(SubnormalPolicy::Zero)
+            .set_bytes_decode_order(vec![
+                BytesFormat::Hex,
+                BytesFormat::Base64(Base64Variant::URL_SAFE_NO_PAD),
+            ]);
+
+        let json = serde_json::to_string(&config).unwrap();
+        let read: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&read).unwrap(), json);
+
+        assert_eq!(read.bytes_format(), BytesFormat::Hex);
+        assert_eq!(read.hex_prefix_lengths(), Some(&[20, 32][..]));
+        assert_eq!(read.bytes_prefix(), Some("hex:"));
+        assert_eq!(read.indent(), b"\t");
+        assert_eq!(read.max_depth(), Some(16));
+        assert_eq!(read.unit_as(), UnitRepr::EmptyObject);
+        assert_eq!(read.subnormal_policy(), SubnormalPolicy::Zero);
+        assert_eq!(
+            read.bytes_decode_order(),
+            Some(
+                &[
+                    BytesFormat::Hex,
+                    BytesFormat::Base64(Base64Variant::URL_SAFE_NO_PAD)
+                ][..]
+            )
+        );
+
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["bytes_format"], "hex");
+        assert_eq!(value["bytes_decode_order"][1], "base64urlnopad");
+
+        let config = Config::default().set_bytes_base64_standard_no_pad();
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["bytes_format"], "base64nopad");
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_config_round_trip_checksum() {
+        let config =
+            Config::default().set_bytes_hex_checksum(ChecksumAlgo::Eip1191 { chain_id: 30 });
+        let json = serde_json::to_string(&config).unwrap();
+        let read: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.hex_checksum(), ChecksumAlgo::Eip1191 { chain_id: 30 });
+    }
+
+    #[test]
+    fn test_config_deserialize_partial() {
+        let config: Config =
+            serde_json::from_str(r#"{"bytes_format":"base64url","force_sign":true}"#).unwrap();
+        assert_eq!(
+            config.bytes_format(),
+            BytesFormat::Base64(Base64Variant::URL_SAFE)
+        );
+        assert!(config.force_sign());
+        assert_eq!(config.indent(), b"  ");
+
+        assert!(serde_json::from_str::<Config>(r#"{"bytes_format":"base58"}"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{"indent":"--"}"#).is_err());
+
+        #[derive(Debug)]
+        struct Latin1;
+
+        impl crate::ByteCodec for Latin1 {
+            fn encode(&self, input: &[u8]) -> String {
+                input.iter().map(|&b| char::from(b)).collect()
+            }
+
+            fn decode(&self, input: &str) -> Result<Vec<u8>, String> {
+                input
+                    .chars()
+                    .map(|c| u8::try_from(c).map_err(|_| alloc::format!("{:?} is not Latin-1", c)))
+                    .collect()
+            }
+        }
+
+        static LATIN1: Latin1 = Latin1;
+        let config = Config::default().set_bytes_codec(&LATIN1);
+        assert!(serde_json::to_string(&config).is_err());
+    }
+}
@@ -1,14 +1,36 @@
 // Bytes deserialization utilities
 
-use crate::{BytesFormat, Config};
-use serde::de::Visitor;
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+
+use crate::{
+    Base64Variant, ByteCodec, BytesArrayRadix, BytesFormat, Config, Error,
+    codec::{base32, base36, crc32, hex, protobuf, rlp, uuencode},
+    de::{
+        context::{Context, bytes_error},
+        visitor::check_string_len,
+    },
+};
+use serde::{
+    Deserialize,
+    de::{DeserializeSeed, Error as _, SeqAccess, Visitor},
+};
 
 /// Deserializes bytes from JSON format based on the configuration
 ///
+/// String formats decode into a freshly allocated buffer that is handed to
+/// the visitor with `visit_byte_buf`, so owned targets such as `Vec<u8>` take
+/// it without a second copy. Borrowed `&'de [u8]` targets are only possible
+/// with `BytesFormat::Utf8`: otherwise the decoded bytes do not exist in the
+/// input. The default array format is
+/// read element by element and likewise cannot borrow.
+///
 /// # Arguments
 ///
 /// * `deserializer` - A `serde_json::de::Deserializer` (consumed)
 /// * `config` - Configuration that determines the deserialization format
+/// * `format` - The bytes format in effect, which a depth or type format may
+///   set in place of `config.bytes_format`
+/// * `context` - State of the `from_*` call, which keeps byte decoding errors
 /// * `visitor` - A visitor that implements `Visitor<'de>`
 ///
 /// # Returns
@@ -17,18 +39,422 @@ use serde::de::Visitor;
 pub(crate) fn de_bytes<'de, D, V>(
     deserializer: D,
     config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    de_bytes_from(
+        deserializer,
+        config,
+        format,
+        Step::Placeholder,
+        context,
+        visitor,
+    )
+}
+
+/// A step of `de_bytes`, in the order the steps apply
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    Placeholder,
+    Untransform,
+    ChecksumSuffix,
+    DecodeOrder,
+    EmptyFormat,
+    Format,
+}
+
+/// Deserializes bytes like `de_bytes`, skipping the steps before `step`
+fn de_bytes_from<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    step: Step,
+    context: Option<&Context>,
     visitor: V,
 ) -> Result<V::Value, D::Error>
 where
     D: serde::de::Deserializer<'de>,
     V: Visitor<'de>,
 {
-    match config.bytes_format {
+    if step <= Step::Placeholder
+        && let Some(placeholder) = &config.bytes_empty_placeholder
+    {
+        return de_bytes_or_placeholder(
+            deserializer,
+            config,
+            format,
+            context,
+            placeholder,
+            visitor,
+        );
+    }
+    if step <= Step::Untransform
+        && let Some(untransform) = &config.bytes_untransform
+    {
+        let bytes = de_bytes_from(
+            deserializer,
+            config,
+            format,
+            Step::ChecksumSuffix,
+            context,
+            OwnedBytesVisitor,
+        )?;
+        return visitor.visit_byte_buf((untransform.0)(&bytes));
+    }
+    if step <= Step::ChecksumSuffix && config.bytes_checksum_suffix {
+        let mut bytes = de_bytes_from(
+            deserializer,
+            config,
+            format,
+            Step::DecodeOrder,
+            context,
+            OwnedBytesVisitor,
+        )?;
+        let Some(split) = bytes.len().checked_sub(4) else {
+            return Err(bytes_error(
+                context,
+                Error::bytes(
+                    format,
+                    format!("{} bytes are too short for a crc32 suffix", bytes.len()),
+                ),
+            ));
+        };
+        let expected = u32::from_be_bytes(bytes[split..].try_into().unwrap());
+        bytes.truncate(split);
+        let actual = crc32::checksum(&bytes);
+        if actual != expected {
+            return Err(bytes_error(context, Error::checksum(expected, actual)));
+        }
+        return visitor.visit_byte_buf(bytes);
+    }
+    if step <= Step::DecodeOrder
+        && let Some(order) = &config.bytes_decode_order
+    {
+        return de_bytes_in_order(deserializer, config, order, visitor);
+    }
+    if step <= Step::EmptyFormat
+        && let Some(empty_format) = config.bytes_empty_format.filter(|f| *f != format)
+    {
+        return de_bytes_or_empty(deserializer, config, format, context, empty_format, visitor);
+    }
+
+    if format == BytesFormat::Default
+        && let Some(radix) = config.bytes_array_radix
+    {
+        return de_bytes_radix_array(deserializer, radix, context, visitor);
+    }
+
+    match format {
+        BytesFormat::Default if config.bytes_default_signed => {
+            de_bytes_signed_array(deserializer, visitor)
+        }
         BytesFormat::Default => de_bytes_array(deserializer, visitor),
-        BytesFormat::Hex => de_bytes_hex(deserializer, config, visitor),
-        BytesFormat::Base64 => de_bytes_base64(deserializer, false, visitor),
-        BytesFormat::Base64UrlSafe => de_bytes_base64(deserializer, true, visitor),
+        BytesFormat::LengthPrefixed => de_bytes_length_prefixed(deserializer, context, visitor),
+        BytesFormat::Hex | BytesFormat::Rlp | BytesFormat::Protobuf => {
+            de_bytes_hex(deserializer, config, format, context, visitor)
+        }
+        BytesFormat::HexOrInteger => {
+            de_bytes_hex_or_integer(deserializer, config, context, visitor)
+        }
+        BytesFormat::Base32 | BytesFormat::ZBase32 => {
+            de_bytes_base32(deserializer, config, format, context, visitor)
+        }
+        BytesFormat::Base36 => de_bytes_base36(deserializer, config, context, visitor),
+        BytesFormat::DataUri => de_bytes_data_uri(deserializer, config, context, visitor),
+        BytesFormat::UuEncode => de_bytes_uuencode(deserializer, config, context, visitor),
+        BytesFormat::MongoExtended => {
+            de_bytes_mongo_extended(deserializer, config, context, visitor)
+        }
+        BytesFormat::NodeBuffer => de_bytes_node_buffer(deserializer, context, visitor),
+        BytesFormat::HexAndLen => de_bytes_hex_and_len(deserializer, config, context, visitor),
+        BytesFormat::Codec(codec) => de_bytes_codec(deserializer, config, codec, context, visitor),
+        BytesFormat::Utf8 => de_bytes_utf8(deserializer, config, context, visitor),
+        BytesFormat::HexOrBase64 => de_bytes_hex_or_base64(deserializer, config, context, visitor),
+        #[allow(deprecated)]
+        BytesFormat::Base64(_)
+        | BytesFormat::Base64UrlSafe
+        | BytesFormat::Base64Custom
+        | BytesFormat::Base64Mime
+        | BytesFormat::Base64Crypt => {
+            de_bytes_base64(deserializer, config, format, context, visitor)
+        }
+    }
+}
+
+/// Deserializes bytes like `de_bytes`, substituting empty bytes for input that fails to decode
+///
+/// The decoding error is kept as recovered from in `context`. Errors that are
+/// not byte decoding errors, such as a number where a string is expected, are
+/// returned as usual.
+#[cfg(feature = "std")]
+pub(crate) fn de_bytes_recovering<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: &Context,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    // A decoding error of an earlier value was handled by its target
+    context.take_bytes_error();
+    match de_bytes(
+        deserializer,
+        config,
+        format,
+        Some(context),
+        OwnedBytesVisitor,
+    ) {
+        Ok(bytes) => visitor.visit_byte_buf(bytes),
+        Err(e) => match context.take_bytes_error() {
+            Some(error) => {
+                context.recover(error);
+                visitor.visit_byte_buf(Vec::new())
+            }
+            None => Err(e),
+        },
+    }
+}
+
+/// Deserializes bytes like `de_bytes` into an owned buffer
+pub(crate) fn de_bytes_owned<'de, D>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+) -> Result<Vec<u8>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    de_bytes(deserializer, config, format, context, OwnedBytesVisitor)
+}
+
+/// Collects bytes from any byte representation into an owned buffer
+struct OwnedBytesVisitor;
+
+impl<'v> Visitor<'v> for OwnedBytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("bytes")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'v>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Deserializes bytes by trying each format of `order` in turn
+///
+/// The input is buffered as a `serde_json::Value` so that it can be decoded
+/// more than once. The first format that succeeds wins; if none does, the
+/// error lists the failure of every format.
+fn de_bytes_in_order<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    order: &[BytesFormat],
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    let mut errors = Vec::with_capacity(order.len());
+    for &format in order {
+        match de_bytes_from(
+            &value,
+            config,
+            format,
+            Step::EmptyFormat,
+            None,
+            OwnedBytesVisitor,
+        ) {
+            Ok(bytes) => return visitor.visit_byte_buf(bytes),
+            Err(e) => errors.push(format!("{}: {}", format.error_name(), e)),
+        }
+    }
+
+    Err(D::Error::custom(format!(
+        "no bytes format matched ({})",
+        errors.join("; ")
+    )))
+}
+
+/// Deserializes bytes in `format`, also accepting empty bytes written in
+/// `empty_format`
+///
+/// The input is buffered as a `serde_json::Value` so that it can be decoded
+/// twice. Non-empty bytes in `empty_format` are rejected.
+fn de_bytes_or_empty<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    empty_format: BytesFormat,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    if let Ok(bytes) = de_bytes_from(
+        &value,
+        config,
+        empty_format,
+        Step::Format,
+        None,
+        OwnedBytesVisitor,
+    ) && bytes.is_empty()
+    {
+        return visitor.visit_byte_buf(bytes);
+    }
+
+    let bytes = de_bytes_from(
+        &value,
+        config,
+        format,
+        Step::Format,
+        context,
+        OwnedBytesVisitor,
+    )
+    .map_err(D::Error::custom)?;
+    visitor.visit_byte_buf(bytes)
+}
+
+/// Deserializes bytes in `format`, reading `placeholder` as empty
+///
+/// The input is buffered as a `serde_json::Value` so that it can be compared
+/// with the placeholder before it is decoded.
+fn de_bytes_or_placeholder<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    placeholder: &str,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    let value = serde_json::Value::deserialize(deserializer)?;
+    if value.as_str() == Some(placeholder) {
+        return visitor.visit_byte_buf(Vec::new());
+    }
+
+    let bytes = de_bytes_from(
+        &value,
+        config,
+        format,
+        Step::Untransform,
+        context,
+        OwnedBytesVisitor,
+    )
+    .map_err(D::Error::custom)?;
+    visitor.visit_byte_buf(bytes)
+}
+
+/// Strips the configured bytes prefix (such as "b64:"), failing if it is absent
+///
+/// Also enforces `max_string_len` on the string as written, since every
+/// string-based bytes format reads its input through here.
+fn strip_bytes_prefix<'s, E>(
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    v: &'s str,
+) -> Result<&'s str, E>
+where
+    E: serde::de::Error,
+{
+    check_string_len(config, v)?;
+    match &config.bytes_prefix {
+        Some(prefix) => v.strip_prefix(&**prefix).ok_or_else(|| {
+            bytes_error(
+                context,
+                Error::bytes(format, format!("missing bytes prefix {:?}", prefix)),
+            )
+        }),
+        None => Ok(v),
+    }
+}
+
+/// Points a hex decode error at the first character that is not a hex digit
+///
+/// `hex::decode` checks the length before the characters and counts from the
+/// start of the digits, so "0x1g3" fails with `OddLength`. This scans `v` the
+/// way the decoder reads it, skipping `0x` prefixes, separators and ignored
+/// whitespace, and reports the character at its byte index in `v` plus
+/// `offset`. `error` is returned unchanged when every character is valid.
+fn locate_hex_error(
+    config: &Config,
+    v: &str,
+    offset: usize,
+    error: ::hex::FromHexError,
+) -> ::hex::FromHexError {
+    let separator = config.hex_separator.as_deref().filter(|s| !s.is_empty());
+    let mut chars = v.char_indices().peekable();
+    let mut at_start = true;
+    while let Some((index, c)) = chars.next() {
+        if config.hex_ignore_whitespace && c.is_ascii_whitespace() {
+            continue;
+        }
+        if let Some(separator) = separator
+            && v[index..].starts_with(separator)
+        {
+            // Skip the rest of the separator
+            while chars
+                .next_if(|&(i, _)| i < index + separator.len())
+                .is_some()
+            {}
+            continue;
+        }
+        if c == '0'
+            && (at_start || config.hex_group_prefix.is_some())
+            && matches!(chars.peek(), Some((_, 'x' | 'X')))
+        {
+            chars.next();
+        } else if !c.is_ascii_hexdigit() {
+            return ::hex::FromHexError::InvalidHexCharacter {
+                c,
+                index: offset + index,
+            };
+        }
+        at_start = false;
     }
+    error
 }
 
 /// Deserializes bytes from a JSON array of numbers [1, 2, 3]
@@ -40,27 +466,239 @@ where
     deserializer.deserialize_bytes(visitor)
 }
 
+/// Deserializes bytes from a JSON array of signed numbers [-1, 0, 127]
+pub(crate) fn de_bytes_signed_array<'de, D, V>(
+    deserializer: D,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct SignedBytesVisitor<V> {
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for SignedBytesVisitor<V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("an array of integers in -128..=127")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(b) = seq.next_element::<i8>()? {
+                bytes.push(b as u8);
+            }
+            self.visitor.visit_byte_buf(bytes)
+        }
+    }
+
+    deserializer.deserialize_seq(SignedBytesVisitor { visitor })
+}
+
+/// Deserializes bytes from a JSON array of prefixed strings ["0x01", "0xff"]
+///
+/// Elements may also be plain numbers in `0..=255`.
+pub(crate) fn de_bytes_radix_array<'de, D, V>(
+    deserializer: D,
+    radix: BytesArrayRadix,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct RadixBytesVisitor<'a, V> {
+        radix: BytesArrayRadix,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for RadixBytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(
+                formatter,
+                "an array of bytes written as \"{}\" strings",
+                self.radix.prefix()
+            )
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            let byte = RadixByte {
+                radix: self.radix,
+                context: self.context,
+            };
+            while let Some(b) = seq.next_element_seed(byte)? {
+                bytes.push(b);
+            }
+            self.visitor.visit_byte_buf(bytes)
+        }
+    }
+
+    /// One element of a radix array
+    #[derive(Clone, Copy)]
+    struct RadixByte<'a> {
+        radix: BytesArrayRadix,
+        context: Option<&'a Context>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for RadixByte<'_> {
+        type Value = u8;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<u8, D::Error>
+        where
+            D: serde::de::Deserializer<'de>,
+        {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for RadixByte<'_> {
+        type Value = u8;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(
+                formatter,
+                "a byte written as a \"{}\" string",
+                self.radix.prefix()
+            )
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<u8, E>
+        where
+            E: serde::de::Error,
+        {
+            u8::try_from(v).map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(v), &self))
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<u8, E>
+        where
+            E: serde::de::Error,
+        {
+            let prefix = self.radix.prefix();
+            v.get(..2)
+                .filter(|p| p.eq_ignore_ascii_case(prefix))
+                .and_then(|_| u8::from_str_radix(&v[2..], self.radix.radix()).ok())
+                .filter(|_| !v[2..].starts_with(['+', '-']))
+                .ok_or_else(|| {
+                    bytes_error(
+                        self.context,
+                        Error::bytes(
+                            BytesFormat::Default,
+                            format!("invalid byte {:?}, expected a {:?} string", v, prefix),
+                        ),
+                    )
+                })
+        }
+    }
+
+    deserializer.deserialize_seq(RadixBytesVisitor {
+        radix,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a JSON array of numbers headed by their count [3, 1, 2, 255]
+///
+/// Fails with `Error::LengthMismatch` if the header differs from the number
+/// of bytes that follow.
+pub(crate) fn de_bytes_length_prefixed<'de, D, V>(
+    deserializer: D,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct LengthPrefixedBytesVisitor<'a, V> {
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for LengthPrefixedBytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("an array of a length followed by that many bytes")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let expected: usize = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let mut bytes = Vec::with_capacity(expected.min(4096));
+            while let Some(b) = seq.next_element()? {
+                bytes.push(b);
+            }
+            if bytes.len() != expected {
+                return Err(bytes_error(
+                    self.context,
+                    Error::length(expected, bytes.len()),
+                ));
+            }
+            self.visitor.visit_byte_buf(bytes)
+        }
+    }
+
+    deserializer.deserialize_seq(LengthPrefixedBytesVisitor { context, visitor })
+}
+
 /// Deserializes bytes from a hexadecimal string "0x1234..." or "1234..."
+///
+/// With `BytesFormat::Rlp` the decoded bytes are additionally unwrapped as an
+/// RLP byte string, and with `BytesFormat::Protobuf` their varint length prefix
+/// is checked and removed.
 pub(crate) fn de_bytes_hex<'de, D, V>(
     deserializer: D,
-    _config: &Config,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
     visitor: V,
 ) -> Result<V::Value, D::Error>
 where
     D: serde::de::Deserializer<'de>,
     V: Visitor<'de>,
 {
-    struct HexBytesVisitor<V> {
+    struct HexBytesVisitor<'a, V> {
+        config: &'a Config,
+        format: BytesFormat,
+        context: Option<&'a Context>,
         visitor: V,
     }
 
-    impl<'de, V> Visitor<'de> for HexBytesVisitor<V>
+    impl<'de, V> Visitor<'de> for HexBytesVisitor<'_, V>
     where
         V: Visitor<'de>,
     {
         type Value = V::Value;
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
             formatter.write_str("a hexadecimal string")
         }
 
@@ -68,14 +706,62 @@ where
         where
             E: serde::de::Error,
         {
-            let hex_str = if v.starts_with("0x") || v.starts_with("0X") {
-                &v[2..]
+            // Kept as written to locate an invalid character
+            let written = strip_bytes_prefix(self.config, self.format, self.context, v)?;
+            let offset = v.len() - written.len();
+            let v = if self.config.hex_ignore_whitespace {
+                Cow::Owned(
+                    written
+                        .chars()
+                        .filter(|c| !c.is_ascii_whitespace())
+                        .collect(),
+                )
+            } else {
+                Cow::Borrowed(written)
+            };
+            let v = match &self.config.hex_separator {
+                Some(separator) if !separator.is_empty() => Cow::Owned(v.replace(&**separator, "")),
+                _ => v,
+            };
+            let hex_str = if self.config.hex_group_prefix.is_some() {
+                // 'x' is not a hex digit, so every "0x" is a group prefix
+                Cow::Owned(v.replace("0x", "").replace("0X", ""))
+            } else if v.starts_with("0x") || v.starts_with("0X") {
+                Cow::Borrowed(&v[2..])
             } else {
-                v
+                Cow::Borrowed(&*v)
             };
-            let bytes = hex::decode(hex_str)
-                .map_err(|e| E::custom(format!("invalid hex string: {}", e)))?;
-            self.visitor.visit_bytes(&bytes)
+            let mut bytes = hex::decode(&hex_str).map_err(|e| {
+                bytes_error(
+                    self.context,
+                    Error::hex(locate_hex_error(self.config, written, offset, e)),
+                )
+            })?;
+            if self.config.hex_reverse {
+                bytes.reverse();
+            }
+            if self.format == BytesFormat::Rlp {
+                let bytes = rlp::decode(&bytes).map_err(|e| {
+                    bytes_error(
+                        self.context,
+                        Error::bytes(BytesFormat::Rlp, format!("invalid rlp string: {}", e)),
+                    )
+                })?;
+                return self.visitor.visit_bytes(bytes);
+            }
+            if self.format == BytesFormat::Protobuf {
+                let bytes = protobuf::decode(&bytes).map_err(|e| {
+                    bytes_error(
+                        self.context,
+                        Error::bytes(
+                            BytesFormat::Protobuf,
+                            format!("invalid length-prefixed bytes: {}", e),
+                        ),
+                    )
+                })?;
+                return self.visitor.visit_bytes(bytes);
+            }
+            self.visitor.visit_byte_buf(bytes)
         }
 
         fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
@@ -86,61 +772,784 @@ where
         }
     }
 
-    deserializer.deserialize_str(HexBytesVisitor { visitor })
+    deserializer.deserialize_str(HexBytesVisitor {
+        config,
+        format,
+        context,
+        visitor,
+    })
 }
 
-/// Deserializes bytes from a Base64 string
-///
-/// # Arguments
+/// Deserializes bytes from a JSON number 256 or a hexadecimal string "0x0100"
 ///
-/// * `url_safe` - If true, uses URL-safe Base64 decoding, otherwise uses standard Base64
-pub(crate) fn de_bytes_base64<'de, D, V>(
+/// A number becomes its minimal big-endian bytes, at least one byte long.
+pub(crate) fn de_bytes_hex_or_integer<'de, D, V>(
     deserializer: D,
-    url_safe: bool,
+    config: &Config,
+    context: Option<&Context>,
     visitor: V,
 ) -> Result<V::Value, D::Error>
 where
     D: serde::de::Deserializer<'de>,
     V: Visitor<'de>,
 {
-    struct Base64BytesVisitor<V> {
-        url_safe: bool,
+    use serde::de::IntoDeserializer as _;
+
+    struct HexOrIntegerBytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
         visitor: V,
     }
 
-    impl<'de, V> Visitor<'de> for Base64BytesVisitor<V>
+    impl<'de, V> Visitor<'de> for HexOrIntegerBytesVisitor<'_, V>
     where
         V: Visitor<'de>,
     {
         type Value = V::Value;
 
-        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-            formatter.write_str("a base64 string")
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("an unsigned integer or a hexadecimal string")
         }
 
-        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
         where
             E: serde::de::Error,
         {
-            use base64::{Engine as _, engine::general_purpose};
-            let engine = if self.url_safe {
-                &general_purpose::URL_SAFE
-            } else {
-                &general_purpose::STANDARD
-            };
-            let bytes = engine
-                .decode(v)
-                .map_err(|e| E::custom(format!("invalid base64 string: {}", e)))?;
-            self.visitor.visit_bytes(&bytes)
+            let be = v.to_be_bytes();
+            let skip = (v.leading_zeros() / 8).min(7) as usize;
+            self.visitor.visit_bytes(&be[skip..])
         }
 
-        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
         where
             E: serde::de::Error,
         {
-            self.visit_str(&v)
+            de_bytes_hex(
+                v.into_deserializer(),
+                self.config,
+                BytesFormat::HexOrInteger,
+                self.context,
+                self.visitor,
+            )
         }
     }
 
-    deserializer.deserialize_str(Base64BytesVisitor { url_safe, visitor })
+    deserializer.deserialize_any(HexOrIntegerBytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a 0x-prefixed hex string "0x0102" or a standard Base64 string
+///
+/// A string is hex if it is `0x` followed by an even number of hex digits,
+/// and Base64 otherwise.
+pub(crate) fn de_bytes_hex_or_base64<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct HexOrBase64BytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for HexOrBase64BytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a 0x-prefixed hexadecimal or base64 string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            use base64::{Engine as _, engine::general_purpose};
+
+            let v = strip_bytes_prefix(self.config, BytesFormat::HexOrBase64, self.context, v)?;
+            let bytes = if hex::is_prefixed(v) {
+                hex::decode(&v[2..]).map_err(|e| bytes_error(self.context, Error::hex(e)))?
+            } else {
+                general_purpose::STANDARD
+                    .decode(v)
+                    .map_err(|e| bytes_error(self.context, Error::base64(e)))?
+            };
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(HexOrBase64BytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a Base64 string
+///
+/// The alphabet is selected by `format`: URL-safe for
+/// `Base64UrlSafe`, the configured engine for `Base64Custom`, unpadded bcrypt
+/// for `Base64Crypt`, and standard Base64 otherwise. The standard and
+/// URL-safe alphabets require the padding the format writes, unless
+/// `config.base64_strict_padding` is off and either is accepted; a custom
+/// engine applies its own padding rules. `Base64Mime` ignores
+/// whitespace such as line breaks. With `config.base64_accept_both_alphabets`,
+/// the standard, URL-safe and MIME formats accept characters from either
+/// alphabet, and whitespace. `Base64Crypt` is never affected.
+pub(crate) fn de_bytes_base64<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct Base64BytesVisitor<'a, V> {
+        config: &'a Config,
+        format: BytesFormat,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for Base64BytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a base64 string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            use base64::{
+                Engine as _, alphabet,
+                engine::{DecodePaddingMode, GeneralPurpose, general_purpose},
+            };
+
+            let variant = self.format.base64_variant();
+            let padding = match (self.config.base64_strict_padding, variant) {
+                (false, _) => DecodePaddingMode::Indifferent,
+                (true, Some(Base64Variant { pad: false, .. })) => DecodePaddingMode::RequireNone,
+                (true, _) => DecodePaddingMode::RequireCanonical,
+            };
+            let padding = general_purpose::PAD.with_decode_padding_mode(padding);
+            let standard = GeneralPurpose::new(&alphabet::STANDARD, padding);
+            let url_safe = GeneralPurpose::new(&alphabet::URL_SAFE, padding);
+            const CRYPT: GeneralPurpose =
+                GeneralPurpose::new(&alphabet::BCRYPT, general_purpose::NO_PAD);
+
+            let v = strip_bytes_prefix(self.config, self.format, self.context, v)?;
+            if self.config.validate_base64_length {
+                let len = v.chars().filter(|c| !c.is_ascii_whitespace()).count();
+                if len % 4 == 1 {
+                    return Err(bytes_error(
+                        self.context,
+                        Error::bytes(self.format, format!("invalid base64 length {}", len)),
+                    ));
+                }
+            }
+            let bytes = match (self.format, &self.config.base64_engine) {
+                (BytesFormat::Base64Custom, Some(engine)) => engine.decode(v),
+                (BytesFormat::Base64Custom, None) => {
+                    return Err(E::custom("no custom base64 engine configured"));
+                }
+                (BytesFormat::Base64Crypt, _) => CRYPT.decode(v),
+                (_, _) if self.config.base64_accept_both_alphabets => {
+                    // The alphabets differ only in the last two characters
+                    let v: String = v
+                        .chars()
+                        .filter(|c| !c.is_ascii_whitespace())
+                        .map(|c| match c {
+                            '-' => '+',
+                            '_' => '/',
+                            c => c,
+                        })
+                        .collect();
+                    standard.decode(v)
+                }
+                _ if variant.is_some_and(|variant| variant.url_safe) => url_safe.decode(v),
+                (BytesFormat::Base64Mime, _) => {
+                    let v: String = v.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+                    standard.decode(v)
+                }
+                _ => standard.decode(v),
+            }
+            .map_err(|e| bytes_error(self.context, Error::base64(e)))?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(Base64BytesVisitor {
+        config,
+        format,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from an RFC 4648 base32 string, or an unpadded z-base-32 string
+///
+/// The alphabet is selected by `format`. Input in the other case
+/// is accepted when `config.bytes_case_insensitive_decode` is set.
+pub(crate) fn de_bytes_base32<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    format: BytesFormat,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct Base32BytesVisitor<'a, V> {
+        config: &'a Config,
+        format: BytesFormat,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for Base32BytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a base32 string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v = strip_bytes_prefix(self.config, self.format, self.context, v)?;
+            let zbase32 = self.format == BytesFormat::ZBase32;
+            let (alphabet, pad) = if zbase32 {
+                (base32::ZBASE32, false)
+            } else {
+                (base32::RFC4648, true)
+            };
+            let bytes = match (self.config.bytes_case_insensitive_decode, zbase32) {
+                (true, true) => base32::decode(alphabet, &v.to_ascii_lowercase(), pad),
+                (true, false) => base32::decode(alphabet, &v.to_ascii_uppercase(), pad),
+                (false, _) => base32::decode(alphabet, v, pad),
+            }
+            .map_err(|e| {
+                let name = if zbase32 { "z-base-32" } else { "base32" };
+                bytes_error(
+                    self.context,
+                    Error::bytes(self.format, format!("invalid {} string: {}", name, e)),
+                )
+            })?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(Base32BytesVisitor {
+        config,
+        format,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a lowercase base36 string
+///
+/// Uppercase input is accepted when `config.bytes_case_insensitive_decode` is set.
+pub(crate) fn de_bytes_base36<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct Base36BytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for Base36BytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a base36 string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v = strip_bytes_prefix(self.config, BytesFormat::Base36, self.context, v)?;
+            let bytes = if self.config.bytes_case_insensitive_decode {
+                base36::decode(&v.to_ascii_lowercase())
+            } else {
+                base36::decode(v)
+            }
+            .map_err(|e| {
+                bytes_error(
+                    self.context,
+                    Error::bytes(BytesFormat::Base36, format!("invalid base36 string: {}", e)),
+                )
+            })?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(Base36BytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a base64 data URI such as `"data:image/png;base64,..."`
+///
+/// The `data:` scheme is matched case-insensitively and the media type may
+/// carry parameters. URIs without the `;base64` marker are rejected. The data
+/// is decoded as padded standard base64 under the same options as
+/// `BytesFormat::Base64`: `base64_strict_padding`,
+/// `base64_accept_both_alphabets` and `validate_base64_length`.
+pub(crate) fn de_bytes_data_uri<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct DataUriBytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for DataUriBytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a base64 data URI")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            use base64::{
+                Engine as _, alphabet,
+                engine::{DecodePaddingMode, GeneralPurpose, general_purpose},
+            };
+
+            let invalid = |message: &str| {
+                bytes_error(
+                    self.context,
+                    Error::bytes(
+                        BytesFormat::DataUri,
+                        format!("invalid data URI: {}", message),
+                    ),
+                )
+            };
+
+            let v = strip_bytes_prefix(self.config, BytesFormat::DataUri, self.context, v)?;
+            let uri = match v.get(..5) {
+                Some(scheme) if scheme.eq_ignore_ascii_case("data:") => &v[5..],
+                _ => return Err(invalid("expected the data: scheme")),
+            };
+            let (media_type, data) = uri.split_once(',').ok_or_else(|| invalid("missing ','"))?;
+            let is_base64 = media_type
+                .rsplit_once(';')
+                .is_some_and(|(_, marker)| marker.eq_ignore_ascii_case("base64"));
+            if !is_base64 {
+                return Err(invalid("only base64 data URIs are supported"));
+            }
+            if self.config.validate_base64_length {
+                let len = data.chars().filter(|c| !c.is_ascii_whitespace()).count();
+                if len % 4 == 1 {
+                    return Err(invalid(&format!("invalid base64 length {}", len)));
+                }
+            }
+            let padding = match self.config.base64_strict_padding {
+                true => DecodePaddingMode::RequireCanonical,
+                false => DecodePaddingMode::Indifferent,
+            };
+            let engine = GeneralPurpose::new(
+                &alphabet::STANDARD,
+                general_purpose::PAD.with_decode_padding_mode(padding),
+            );
+            let bytes = if self.config.base64_accept_both_alphabets {
+                let data: String = data
+                    .chars()
+                    .filter(|c| !c.is_ascii_whitespace())
+                    .map(|c| match c {
+                        '-' => '+',
+                        '_' => '/',
+                        c => c,
+                    })
+                    .collect();
+                engine.decode(data)
+            } else {
+                engine.decode(data)
+            };
+            let bytes = bytes.map_err(|e| bytes_error(self.context, Error::base64(e)))?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(DataUriBytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from uuencoded lines
+pub(crate) fn de_bytes_uuencode<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct UuencodeBytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for UuencodeBytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a uuencoded string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v = strip_bytes_prefix(self.config, BytesFormat::UuEncode, self.context, v)?;
+            let bytes = uuencode::decode(v).map_err(|e| {
+                bytes_error(
+                    self.context,
+                    Error::bytes(
+                        BytesFormat::UuEncode,
+                        format!("invalid uuencoded string: {}", e),
+                    ),
+                )
+            })?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(UuencodeBytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a string as its UTF-8 encoding
+///
+/// Without a bytes prefix, a string borrowed from the input is handed on as
+/// borrowed bytes, so `&[u8]` targets are possible.
+pub(crate) fn de_bytes_utf8<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct Utf8BytesVisitor<'a, V> {
+        config: &'a Config,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for Utf8BytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v = strip_bytes_prefix(self.config, BytesFormat::Utf8, self.context, v)?;
+            self.visitor.visit_bytes(v.as_bytes())
+        }
+
+        fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v = strip_bytes_prefix(self.config, BytesFormat::Utf8, self.context, v)?;
+            self.visitor.visit_borrowed_bytes(v.as_bytes())
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            if self.config.bytes_prefix.is_some() {
+                return self.visit_str(&v);
+            }
+            check_string_len(self.config, &v)?;
+            self.visitor.visit_byte_buf(v.into_bytes())
+        }
+    }
+
+    deserializer.deserialize_str(Utf8BytesVisitor {
+        config,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a string using `codec`
+pub(crate) fn de_bytes_codec<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    codec: &'static dyn ByteCodec,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct CodecBytesVisitor<'a, V> {
+        config: &'a Config,
+        codec: &'static dyn ByteCodec,
+        context: Option<&'a Context>,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for CodecBytesVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("an encoded byte string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let v =
+                strip_bytes_prefix(self.config, BytesFormat::Codec(self.codec), self.context, v)?;
+            let bytes = self.codec.decode(v).map_err(|message| {
+                bytes_error(
+                    self.context,
+                    Error::bytes(BytesFormat::Codec(self.codec), message),
+                )
+            })?;
+            self.visitor.visit_byte_buf(bytes)
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_str(CodecBytesVisitor {
+        config,
+        codec,
+        context,
+        visitor,
+    })
+}
+
+/// Deserializes bytes from a MongoDB extended JSON binary object
+///
+/// The `subType` field is optional and ignored.
+pub(crate) fn de_bytes_mongo_extended<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    use base64::{Engine as _, engine::general_purpose};
+
+    #[derive(Deserialize)]
+    struct MongoBinary {
+        #[serde(rename = "$binary")]
+        binary: MongoBinaryBody,
+    }
+
+    #[derive(Deserialize)]
+    struct MongoBinaryBody {
+        base64: String,
+    }
+
+    let value = MongoBinary::deserialize(deserializer)?;
+    check_string_len(config, &value.binary.base64)?;
+    let bytes = general_purpose::STANDARD
+        .decode(value.binary.base64)
+        .map_err(|e| bytes_error(context, Error::base64(e)))?;
+    visitor.visit_byte_buf(bytes)
+}
+
+/// Deserializes bytes from a Node.js `Buffer.toJSON()` object
+///
+/// Fails unless the `type` field is `"Buffer"`.
+pub(crate) fn de_bytes_node_buffer<'de, D, V>(
+    deserializer: D,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    #[derive(Deserialize)]
+    struct NodeBuffer {
+        r#type: String,
+        data: Vec<u8>,
+    }
+
+    let value = NodeBuffer::deserialize(deserializer)?;
+    if value.r#type != "Buffer" {
+        return Err(bytes_error(
+            context,
+            Error::bytes(
+                BytesFormat::NodeBuffer,
+                format!(
+                    "invalid buffer type {:?}, expected \"Buffer\"",
+                    value.r#type
+                ),
+            ),
+        ));
+    }
+    visitor.visit_byte_buf(value.data)
+}
+
+/// Deserializes bytes from an object holding their count and hex {"len":2,"hex":"0x01ff"}
+///
+/// The `0x` prefix and `len` are optional. Fails with `Error::LengthMismatch`
+/// if `len` differs from the number of decoded bytes.
+pub(crate) fn de_bytes_hex_and_len<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    context: Option<&Context>,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    #[derive(Deserialize)]
+    struct HexAndLen {
+        len: Option<usize>,
+        hex: String,
+    }
+
+    let value = HexAndLen::deserialize(deserializer)?;
+    check_string_len(config, &value.hex)?;
+    let digits = value.hex.strip_prefix("0x").unwrap_or(&value.hex);
+    let bytes = hex::decode(digits).map_err(|e| {
+        let e = locate_hex_error(&Config::default(), &value.hex, 0, e);
+        bytes_error(context, Error::hex(e))
+    })?;
+    if let Some(expected) = value.len
+        && bytes.len() != expected
+    {
+        return Err(bytes_error(context, Error::length(expected, bytes.len())));
+    }
+    visitor.visit_byte_buf(bytes)
 }
@@ -0,0 +1,95 @@
+// Per-call state of the deserializer wrappers
+
+use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+
+use serde::de;
+
+use crate::Error;
+
+/// State of one `from_*` call, shared by the deserializer wrappers
+///
+/// serde errors hold nothing but a message, so a byte decoding error is
+/// returned through serde as its message and kept here as well. The `from_*`
+/// function owns the context and returns the kept error in place of the
+/// serde_json error it was returned as.
+#[derive(Default)]
+pub(crate) struct Context {
+    /// The byte decoding error last returned through serde
+    bytes_error: Cell<Option<Error>>,
+    /// Byte decoding errors recovered from by `from_str_recovering`, which
+    /// reads such fields as empty while this is set
+    #[cfg(feature = "std")]
+    recovered: Option<RefCell<Vec<Error>>>,
+}
+
+impl Context {
+    /// Returns a context that recovers from byte decoding errors
+    #[cfg(feature = "std")]
+    pub(crate) fn recovering() -> Self {
+        Context {
+            recovered: Some(RefCell::default()),
+            ..Context::default()
+        }
+    }
+
+    /// Returns whether byte decoding errors are recovered from
+    #[cfg(feature = "std")]
+    pub(crate) fn is_recovering(&self) -> bool {
+        self.recovered.is_some()
+    }
+
+    /// Keeps `error` as recovered from
+    #[cfg(feature = "std")]
+    pub(crate) fn recover(&self, error: Error) {
+        if let Some(recovered) = &self.recovered {
+            recovered.borrow_mut().push(error);
+        }
+    }
+
+    /// Returns the errors recovered from, in input order
+    #[cfg(feature = "std")]
+    pub(crate) fn into_recovered(self) -> Vec<Error> {
+        self.recovered.map(RefCell::into_inner).unwrap_or_default()
+    }
+
+    /// Returns the error to report for `error`, which a deserialization
+    /// under this context failed with
+    ///
+    /// That is the kept byte decoding error at the position of `error` if
+    /// `error` is the one it was returned as, and `error` itself otherwise,
+    /// for example when the byte decoding error was handled and another error
+    /// followed.
+    pub(crate) fn error(&self, error: serde_json::Error) -> Error {
+        let Some(bytes_error) = self.bytes_error.take() else {
+            return Error::Json(error);
+        };
+        let bytes_error = bytes_error.at(&error);
+        match bytes_error.to_string() == error.to_string() {
+            true => bytes_error,
+            false => Error::Json(error),
+        }
+    }
+
+    /// Takes the byte decoding error last returned through serde
+    #[cfg(feature = "std")]
+    pub(crate) fn take_bytes_error(&self) -> Option<Error> {
+        self.bytes_error.take()
+    }
+}
+
+/// Returns a byte decoding error as a serde error, keeping it in `context`
+pub(crate) fn bytes_error<E>(context: Option<&Context>, error: Error) -> E
+where
+    E: de::Error,
+{
+    let serde_error = E::custom(&error);
+    if let Some(context) = context {
+        context.bytes_error.set(Some(error));
+    }
+    serde_error
+}
@@ -1,9 +1,9 @@
 // Deserializer wrapper for serde_json
 
-use crate::Config;
+use crate::{Config, ResultRepr, result};
 use serde::de::Visitor;
 
-use super::{WrapVisitor, bytes};
+use super::{WrapVisitor, bytes, context::Context, number};
 
 /// A wrapper around `serde_json::Deserializer` that implements `Deserializer<'de>`
 pub struct Deserializer<'a, D> {
@@ -11,16 +11,68 @@ pub struct Deserializer<'a, D> {
     pub inner: D,
     /// Configuration for deserialization
     pub config: &'a Config,
+    /// Current nesting depth of arrays, objects and enum variants
+    pub(crate) depth: usize,
+    /// State of the `from_*` call this deserializer is part of, if any
+    pub(crate) context: Option<&'a Context>,
 }
 
 impl<'a, D> Deserializer<'a, D> {
     /// Creates a new `Deserializer` from an internal `serde_json::Deserializer` with custom config
     pub fn with_config(inner: D, config: &'a Config) -> Self {
-        Deserializer { inner, config }
+        Deserializer {
+            inner,
+            config,
+            depth: 0,
+            context: None,
+        }
+    }
+
+    /// Deserializes the next value of `inner` with `config` instead of the
+    /// config of the surrounding document
+    ///
+    /// Call it from a `deserialize_with` function, passing the deserializer it
+    /// receives, so that one document can mix byte encodings. The counterpart
+    /// on serialize is `WrapValue`.
+    pub fn deserialize_with_config<T>(inner: D, config: &'a Config) -> Result<T, D::Error>
+    where
+        D: serde::de::Deserializer<'a>,
+        T: serde::de::Deserialize<'a>,
+    {
+        T::deserialize(Self::with_config(inner, config))
+    }
+
+    /// Creates a new `Deserializer` that continues at the given nesting depth
+    ///
+    /// Use it when `inner` is already nested inside a document whose depth
+    /// counts towards `max_depth`; `with_config` starts at depth 0.
+    pub fn with_depth(inner: D, config: &'a Config, depth: usize) -> Self {
+        Deserializer {
+            inner,
+            config,
+            depth,
+            context: None,
+        }
+    }
+
+    /// Creates a new `Deserializer` at the given nesting depth that keeps
+    /// byte decoding errors in `context`
+    pub(crate) fn with_state(
+        inner: D,
+        config: &'a Config,
+        depth: usize,
+        context: Option<&'a Context>,
+    ) -> Self {
+        Deserializer {
+            inner,
+            config,
+            depth,
+            context,
+        }
     }
 }
 
-impl<'de, D> serde::de::Deserializer<'de> for Deserializer<'de, D>
+impl<'de, 'a, D> serde::de::Deserializer<'de> for Deserializer<'a, D>
 where
     D: serde::de::Deserializer<'de>,
 {
@@ -30,7 +82,12 @@ where
     where
         V: Visitor<'de>,
     {
-        self.inner.deserialize_any(visitor)
+        self.inner.deserialize_any(WrapVisitor {
+            visitor,
+            config: self.config,
+            depth: self.depth,
+            context: self.context,
+        })
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -44,6 +101,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_i8(visitor)
     }
 
@@ -51,6 +111,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_i16(visitor)
     }
 
@@ -58,6 +121,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_i32(visitor)
     }
 
@@ -65,6 +131,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_i64(visitor)
     }
 
@@ -72,6 +141,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_i128(visitor)
     }
 
@@ -79,6 +151,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_u8(visitor)
     }
 
@@ -86,6 +161,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_u16(visitor)
     }
 
@@ -93,6 +171,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_u32(visitor)
     }
 
@@ -100,6 +181,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_u64(visitor)
     }
 
@@ -107,6 +191,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.accepts_number_strings() {
+            return number::de_number(self.inner, self.config, visitor);
+        }
         self.inner.deserialize_u128(visitor)
     }
 
@@ -114,6 +201,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.numbers_as_strings {
+            return number::de_float(self.inner, visitor);
+        }
         self.inner.deserialize_f32(visitor)
     }
 
@@ -121,6 +211,9 @@ where
     where
         V: Visitor<'de>,
     {
+        if self.config.numbers_as_strings {
+            return number::de_float(self.inner, visitor);
+        }
         self.inner.deserialize_f64(visitor)
     }
 
@@ -135,21 +228,39 @@ where
     where
         V: Visitor<'de>,
     {
-        self.inner.deserialize_str(visitor)
+        self.inner.deserialize_str(WrapVisitor {
+            visitor,
+            config: self.config,
+            depth: self.depth,
+            context: self.context,
+        })
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.inner.deserialize_string(visitor)
+        self.inner.deserialize_string(WrapVisitor {
+            visitor,
+            config: self.config,
+            depth: self.depth,
+            context: self.context,
+        })
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        bytes::de_bytes(self.inner, self.config, visitor)
+        let format = self
+            .config
+            .bytes_format_for_depth(self.depth)
+            .unwrap_or(self.config.bytes_format);
+        #[cfg(feature = "std")]
+        if let Some(context) = self.context.filter(|context| context.is_recovering()) {
+            return bytes::de_bytes_recovering(self.inner, self.config, format, context, visitor);
+        }
+        bytes::de_bytes(self.inner, self.config, format, self.context, visitor)
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -157,14 +268,19 @@ where
         V: Visitor<'de>,
     {
         // self.inner.deserialize_byte_buf(visitor)
-        bytes::de_bytes(self.inner, self.config, visitor)
+        self.deserialize_bytes(visitor)
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
-        self.inner.deserialize_option(visitor)
+        self.inner.deserialize_option(WrapVisitor {
+            visitor,
+            config: self.config,
+            depth: self.depth,
+            context: self.context,
+        })
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -193,7 +309,21 @@ where
     where
         V: Visitor<'de>,
     {
-        self.inner.deserialize_newtype_struct(name, visitor)
+        if let Some(format) = self.config.bytes_format_for_type_name(name) {
+            // The visitor reads the newtype from a deserializer of its own,
+            // which can only hand out the decoded bytes from a local buffer
+            let bytes = bytes::de_bytes_owned(self.inner, self.config, format, self.context)?;
+            return visitor.visit_newtype_struct(serde::de::value::BytesDeserializer::new(&bytes));
+        }
+        self.inner.deserialize_newtype_struct(
+            name,
+            WrapVisitor {
+                visitor,
+                config: self.config,
+                depth: self.depth,
+                context: self.context,
+            },
+        )
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -203,6 +333,8 @@ where
         self.inner.deserialize_seq(WrapVisitor {
             visitor,
             config: self.config,
+            depth: self.depth,
+            context: self.context,
         })
     }
 
@@ -215,6 +347,8 @@ where
             WrapVisitor {
                 visitor,
                 config: self.config,
+                depth: self.depth,
+                context: self.context,
             },
         )
     }
@@ -234,6 +368,8 @@ where
             WrapVisitor {
                 visitor,
                 config: self.config,
+                depth: self.depth,
+                context: self.context,
             },
         )
     }
@@ -245,6 +381,8 @@ where
         self.inner.deserialize_map(WrapVisitor {
             visitor,
             config: self.config,
+            depth: self.depth,
+            context: self.context,
         })
     }
 
@@ -263,6 +401,8 @@ where
             WrapVisitor {
                 visitor,
                 config: self.config,
+                depth: self.depth,
+                context: self.context,
             },
         )
     }
@@ -290,12 +430,24 @@ where
     where
         V: Visitor<'de>,
     {
+        if name == result::RESULT_NAME && self.config.result_repr == ResultRepr::OkError {
+            use serde::de::{Deserialize as _, Error as _};
+
+            let value = serde_json::Value::deserialize(self.inner)?;
+            let tagged = result::ok_error_to_tagged(value).map_err(D::Error::custom)?;
+            // Read back under serde's name so that the tagged form is used as is
+            return Deserializer::with_state(tagged, self.config, self.depth, self.context)
+                .deserialize_enum("Result", variants, visitor)
+                .map_err(D::Error::custom);
+        }
         self.inner.deserialize_enum(
             name,
             variants,
             WrapVisitor {
                 visitor,
                 config: self.config,
+                depth: self.depth,
+                context: self.context,
             },
         )
     }
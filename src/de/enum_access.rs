@@ -1,26 +1,43 @@
 use serde::de::{DeserializeSeed, EnumAccess};
 
-use crate::{Config, de::seed::WrapSeed};
+use crate::{
+    Config,
+    de::{context::Context, seed::WrapSeed, variant_access::WrapVariantAccess},
+};
 
 pub struct WrapEnumAccess<'a, A> {
     pub inner: A,
     pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
 }
 
-impl<'de, A> EnumAccess<'de> for WrapEnumAccess<'de, A>
+impl<'de, 'a, A> EnumAccess<'de> for WrapEnumAccess<'a, A>
 where
     A: EnumAccess<'de>,
 {
     type Error = A::Error;
-    type Variant = A::Variant;
+    type Variant = WrapVariantAccess<'a, A::Variant>;
 
     fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
         V: DeserializeSeed<'de>,
     {
-        self.inner.variant_seed(WrapSeed {
+        let (value, variant) = self.inner.variant_seed(WrapSeed {
             seed,
             config: self.config,
-        })
+            depth: self.depth,
+            context: self.context,
+        })?;
+
+        Ok((
+            value,
+            WrapVariantAccess {
+                inner: variant,
+                config: self.config,
+                depth: self.depth,
+                context: self.context,
+            },
+        ))
     }
 }
@@ -1,29 +1,105 @@
+use alloc::vec::Vec;
 use serde::{Deserialize, de::DeserializeOwned};
-use serde_json::{Result, de::Read};
+use serde_json::de::Read;
 
-use crate::{Config, de::Deserializer};
+#[cfg(feature = "std")]
+use crate::Error;
+use crate::{
+    Config, Result,
+    de::{Deserializer, context::Context},
+    vec_bytes::ByteBuf,
+};
 
-fn from_trait<'de, R, T>(read: R, config: &'de Config) -> Result<T>
+fn from_trait<'de, R, T>(read: R, config: &Config) -> Result<T>
+where
+    R: Read<'de>,
+    T: Deserialize<'de>,
+{
+    from_trait_in(read, config, &Context::default())
+}
+
+fn from_trait_in<'de, R, T>(read: R, config: &Config, context: &Context) -> Result<T>
 where
     R: Read<'de>,
     T: Deserialize<'de>,
 {
     let mut serde_json_de = serde_json::Deserializer::new(read);
-    let de = Deserializer::with_config(&mut serde_json_de, config);
+    let de = Deserializer::with_state(&mut serde_json_de, config, 0, Some(context));
 
-    let value = serde::de::Deserialize::deserialize(de)?;
+    let value = serde::de::Deserialize::deserialize(de).map_err(|e| context.error(e))?;
 
-    serde_json_de.end()?;
+    if !config.allow_trailing_data {
+        serde_json_de.end()?;
+    }
 
     Ok(value)
 }
 
+#[cfg(feature = "std")]
 pub fn from_reader<R, T>(rdr: R, config: &Config) -> Result<T>
 where
     R: std::io::Read,
     T: serde::de::DeserializeOwned,
 {
-    from_trait(serde_json::de::IoRead::new(rdr), config)
+    let Some(limit) = config.max_input_bytes else {
+        return from_trait(serde_json::de::IoRead::new(rdr), config);
+    };
+
+    let mut limited = LimitedReader {
+        inner: rdr,
+        remaining: limit,
+        exceeded: false,
+    };
+    let result = from_trait(serde_json::de::IoRead::new(&mut limited), config);
+    if limited.exceeded {
+        return Err(crate::Error::InputTooLarge { limit });
+    }
+    result
+}
+
+/// Deserializes from a gzip-compressed reader with the given configuration
+///
+/// Decompresses `rdr` as it is read, then deserializes as [`from_reader`]
+/// does. Input of several concatenated gzip members, as produced by
+/// appending to a `.gz` file, is read as one stream. A limit set with
+/// `Config::set_max_input_bytes` applies to the decompressed bytes. Requires
+/// the `gzip` feature.
+#[cfg(feature = "gzip")]
+pub fn from_gzip_reader<R, T>(rdr: R, config: &Config) -> Result<T>
+where
+    R: std::io::Read,
+    T: serde::de::DeserializeOwned,
+{
+    from_reader(flate2::read::MultiGzDecoder::new(rdr), config)
+}
+
+/// A reader that fails once more than `remaining` bytes have been read
+#[cfg(feature = "std")]
+struct LimitedReader<R> {
+    inner: R,
+    remaining: usize,
+    exceeded: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.remaining == 0 {
+            // Input of exactly the limit is fine, so only fail if more follows
+            if self.inner.read(&mut [0u8])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(std::io::Error::other("input size limit exceeded"));
+        }
+        let len = buf.len().min(self.remaining);
+        let n = self.inner.read(&mut buf[..len])?;
+        self.remaining -= n;
+        Ok(n)
+    }
 }
 
 pub fn from_slice<'a, T>(v: &'a [u8], config: &'a Config) -> Result<T>
@@ -40,22 +116,143 @@ where
     from_trait(serde_json::de::StrRead::new(s), config)
 }
 
+/// Deserializes from a string, recovering from byte fields that fail to decode
+///
+/// Each byte field whose input does not decode in the configured bytes format
+/// is read as empty bytes, and its error is returned alongside the value, in
+/// input order. Fields that need a fixed length, such as `bytes_array`, still
+/// fail on empty bytes. Any other error, such as malformed JSON, fails the
+/// whole call as in `from_str`.
+#[cfg(feature = "std")]
+pub fn from_str_recovering<'a, T>(s: &'a str, config: &'a Config) -> Result<(T, Vec<Error>)>
+where
+    T: Deserialize<'a>,
+{
+    let context = Context::recovering();
+    let value = from_trait_in(serde_json::de::StrRead::new(s), config, &context)?;
+    Ok((value, context.into_recovered()))
+}
+
+/// Deserializes a byte slice from a JSON string in the configured bytes format
+///
+/// Unlike `from_str::<Vec<u8>>`, which expects an array of numbers, the input
+/// is always decoded as bytes.
+pub fn from_str_bytes(s: &str, config: &Config) -> Result<Vec<u8>> {
+    from_str::<ByteBuf>(s, config).map(|bytes| bytes.0)
+}
+
+/// Deserializes a `serde_json::Value`, replacing strings that are marked as
+/// bytes with an array of the decoded bytes
+///
+/// A `Value` carries no type information, so only strings that no text would
+/// be mistaken for are decoded: those starting with the bytes prefix when one
+/// is set, otherwise those starting with `0x` when bytes are hex with the
+/// `0x` prefix. Without either nothing is decoded, so `""` and plain text
+/// such as `"cafe"` stay strings. The formats of `bytes_decode_order` are
+/// tried in turn when it is set, and a marked string that decodes in none of
+/// them is kept. Object keys are never decoded.
+#[cfg(feature = "value-bytes")]
+pub fn from_str_decoding_bytes(s: &str, config: &Config) -> Result<serde_json::Value> {
+    let mut value = serde_json::from_str(s)?;
+    let formats = match &config.bytes_decode_order {
+        Some(order) => order,
+        None => core::slice::from_ref(&config.bytes_format),
+    };
+    let configs: Vec<Config> = formats
+        .iter()
+        .map(|format| {
+            let mut config = config.clone_with_bytes_format(*format);
+            config.bytes_decode_order = None;
+            config
+        })
+        .filter(|config| config.bytes_format.is_string() && marker(config).is_some())
+        .collect();
+    if !configs.is_empty() {
+        decode_bytes_in_value(&mut value, &configs);
+    }
+    Ok(value)
+}
+
+/// Start of every string written as bytes under `config`, if it has one that
+/// text would not be mistaken for
+#[cfg(feature = "value-bytes")]
+fn marker(config: &Config) -> Option<&str> {
+    match config.bytes_prefix.as_deref() {
+        Some("") | None => {}
+        prefix => return prefix,
+    }
+    (config.bytes_format == crate::BytesFormat::Hex && config.hex_prefix).then_some("0x")
+}
+
+#[cfg(feature = "value-bytes")]
+fn decode_bytes_in_value(value: &mut serde_json::Value, configs: &[Config]) {
+    use serde_json::Value;
+
+    match value {
+        Value::String(s) => {
+            let decoded = configs
+                .iter()
+                .filter(|config| marker(config).is_some_and(|marker| s.starts_with(marker)))
+                .find_map(|config| {
+                    let de = Deserializer::with_config(Value::String(s.clone()), config);
+                    ByteBuf::deserialize(de).ok()
+                });
+            if let Some(bytes) = decoded {
+                *value = bytes.0.into_iter().map(Value::from).collect();
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                decode_bytes_in_value(value, configs);
+            }
+        }
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                decode_bytes_in_value(value, configs);
+            }
+        }
+        _ => {}
+    }
+}
+
 pub fn from_value<T>(value: serde_json::Value, config: &Config) -> Result<T>
 where
     T: DeserializeOwned,
 {
-    let de = Deserializer::with_config(value, config);
+    let context = Context::default();
+    let de = Deserializer::with_state(value, config, 0, Some(&context));
+
+    let value = serde::de::Deserialize::deserialize(de).map_err(|e| context.error(e))?;
+
+    Ok(value)
+}
+
+/// Deserializes from a borrowed `serde_json::Value` without consuming or cloning it
+///
+/// As with [`from_str`], `T` may borrow strings from `value`.
+pub fn from_value_ref<'a, T>(value: &'a serde_json::Value, config: &'a Config) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let context = Context::default();
+    let de = Deserializer::with_state(value, config, 0, Some(&context));
 
-    let value = serde::de::Deserialize::deserialize(de)?;
+    let value = serde::de::Deserialize::deserialize(de).map_err(|e| context.error(e))?;
 
     Ok(value)
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+    };
     use serde_json::json;
 
     use super::*;
+    use crate::BytesFormat;
 
     #[test]
     fn test_from_str_hex_without_prefix_to_vec_u8() {
@@ -89,4 +286,2672 @@ mod tests {
         let result: Result<TestStruct> = from_value(json, &config);
         assert_eq!(result.unwrap().data, vec![0, 0, 255]);
     }
+
+    #[test]
+    fn test_from_value_ref_hex() {
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        #[derive(Deserialize, Debug)]
+        struct TestStruct<'a> {
+            name: &'a str,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let json = json!({
+            "name": "borrowed",
+            "data": "0x0000ff"
+        });
+
+        let result: TestStruct = from_value_ref(&json, &config).unwrap();
+        assert_eq!(result.name, "borrowed");
+        assert_eq!(result.data, vec![0, 0, 255]);
+        assert_eq!(json["data"], "0x0000ff");
+
+        let json = json!({"name": "x", "data": "0xzz"});
+        let result: Result<TestStruct> = from_value_ref(&json, &config);
+        assert!(matches!(result, Err(crate::Error::HexDecode { .. })));
+    }
+
+    #[test]
+    fn test_round_trip_base64_custom_engine() {
+        use base64::{
+            alphabet,
+            engine::{GeneralPurpose, general_purpose},
+        };
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let engine = GeneralPurpose::new(&alphabet::BCRYPT, general_purpose::NO_PAD);
+        let config = Config::default().set_bytes_base64_custom_engine(engine);
+
+        let test_data = TestStruct {
+            data: vec![0x00, 0x10, 0x83, 0xff, 0xfe],
+        };
+
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"./AB992"}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_from_str_hex_numbers() {
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            gas: u64,
+            nonce: u8,
+            delta: i64,
+        }
+
+        let config = Config::default().enable_hex_numbers();
+
+        let json = r#"{"gas":"0x5208","nonce":7,"delta":"-0x10"}"#;
+        let result: TestStruct = from_str(json, &config).unwrap();
+        assert_eq!(result.gas, 21000);
+        assert_eq!(result.nonce, 7);
+        assert_eq!(result.delta, -16);
+
+        let json = r#"{"gas":"5208","nonce":7,"delta":0}"#;
+        let result: Result<TestStruct> = from_str(json, &config);
+        assert!(result.is_err());
+
+        let json = r#"{"gas":"0x5208","nonce":"0x100","delta":0}"#;
+        let result: Result<TestStruct> = from_str(json, &config);
+        assert!(result.is_err());
+
+        let json = r#"{"gas":"0x5208","nonce":7,"delta":0}"#;
+        let result: Result<TestStruct> = from_str(json, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_enum_variants_with_bytes() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        enum Payload {
+            Struct {
+                #[serde(with = "serde_bytes")]
+                data: Vec<u8>,
+            },
+            Tuple(#[serde(with = "serde_bytes")] Vec<u8>, u8),
+            Newtype(#[serde(with = "serde_bytes")] Vec<u8>),
+            Unit,
+        }
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Wrapper(#[serde(with = "serde_bytes")] Vec<u8>);
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let cases = vec![
+            (
+                Payload::Struct { data: vec![1, 2] },
+                r#"{"Struct":{"data":"0x0102"}}"#,
+            ),
+            (Payload::Tuple(vec![1, 2], 3), r#"{"Tuple":["0x0102",3]}"#),
+            (Payload::Newtype(vec![1, 2]), r#"{"Newtype":"0x0102"}"#),
+            (Payload::Unit, r#""Unit""#),
+        ];
+
+        for (value, expected) in cases {
+            let json = crate::to_string(&value, &config).unwrap();
+            assert_eq!(json, expected);
+
+            let result: Payload = from_str(&json, &config).unwrap();
+            assert_eq!(result, value);
+        }
+
+        let json = crate::to_string(&Wrapper(vec![0xff]), &config).unwrap();
+        assert_eq!(json, r#""0xff""#);
+
+        let result: Wrapper = from_str(&json, &config).unwrap();
+        assert_eq!(result, Wrapper(vec![0xff]));
+    }
+
+    #[test]
+    fn test_round_trip_tagged_enums_with_bytes() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(tag = "type")]
+        enum Internal {
+            Data {
+                #[serde(with = "serde_bytes")]
+                data: Vec<u8>,
+            },
+        }
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(tag = "t", content = "c")]
+        enum Adjacent {
+            Data {
+                #[serde(with = "serde_bytes")]
+                data: Vec<u8>,
+            },
+            Newtype(#[serde(with = "serde_bytes")] Vec<u8>),
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let value = Internal::Data { data: vec![1, 2] };
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"type":"Data","data":"0x0102"}"#);
+
+        let value = Adjacent::Data { data: vec![1, 2] };
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"t":"Data","c":{"data":"0x0102"}}"#);
+        let result: Adjacent = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let value = Adjacent::Newtype(vec![1, 2]);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"t":"Newtype","c":"0x0102"}"#);
+        let result: Adjacent = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_from_str_option_bytes() {
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Option<Vec<u8>>,
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let result: TestStruct = from_str(r#"{"data":"0x0102"}"#, &config).unwrap();
+        assert_eq!(result.data, Some(vec![1, 2]));
+
+        let result: TestStruct = from_str(r#"{"data":null}"#, &config).unwrap();
+        assert_eq!(result.data, None);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_prefix() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![1, 2, 3, 255],
+        };
+
+        let config = Config::default()
+            .set_bytes_base64()
+            .set_bytes_prefix("b64:");
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"b64:AQID/w=="}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: Result<TestStruct> = from_str(r#"{"data":"AQID/w=="}"#, &config);
+        assert!(result.is_err());
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_bytes_prefix("hex:");
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"hex:0x010203ff"}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_format_per_type() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Hash(#[serde(with = "serde_bytes")] Vec<u8>);
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            hash: Hash,
+            parent: Option<Hash>,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            hash: Hash(vec![0x01, 0x02]),
+            parent: Some(Hash(vec![0xff])),
+            data: vec![0x01, 0x02],
+        };
+
+        let config = Config::default()
+            .set_bytes_base64()
+            .enable_hex_prefix()
+            .set_bytes_format_for_type_name("Hash", BytesFormat::Hex);
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"hash":"0x0102","parent":"0xff","data":"AQI="}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = Config::default()
+            .set_bytes_base64()
+            .enable_hex_prefix()
+            .set_bytes_format_per_type::<Hash>(BytesFormat::Hex);
+        assert_eq!(
+            config.bytes_format_for_type_name("Hash"),
+            Some(BytesFormat::Hex)
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = config.clear_bytes_formats_per_type();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"hash":"AQI=","parent":"/w==","data":"AQI="}"#);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_format_for_depth() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Inner {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            extra: Option<Vec<u8>>,
+            list: Vec<serde_bytes::ByteBuf>,
+        }
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Outer {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            inner: Inner,
+        }
+
+        let test_data = Outer {
+            data: vec![0x01, 0x02],
+            inner: Inner {
+                data: vec![0x01, 0x02],
+                extra: Some(vec![0xff]),
+                list: vec![serde_bytes::ByteBuf::from(vec![0x03])],
+            },
+        };
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_bytes_format_for_depth(2, BytesFormat::Base64(crate::Base64Variant::STANDARD));
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"0x0102","inner":{"data":"AQI=","extra":"/w==","list":["Aw=="]}}"#
+        );
+        let result: Outer = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = config.set_bytes_format_for_depth(3, BytesFormat::Hex);
+        assert_eq!(config.bytes_format_for_depth(1), None);
+        assert_eq!(
+            config.bytes_format_for_depth(2),
+            Some(BytesFormat::Base64(crate::Base64Variant::STANDARD))
+        );
+        assert_eq!(config.bytes_format_for_depth(4), Some(BytesFormat::Hex));
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"0x0102","inner":{"data":"AQI=","extra":"/w==","list":["0x03"]}}"#
+        );
+        let result: Outer = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = config.clear_bytes_formats_for_depth();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"0x0102","inner":{"data":"0x0102","extra":"0xff","list":["0x03"]}}"#
+        );
+    }
+
+    #[test]
+    fn test_round_trip_result() {
+        use crate::ResultRepr;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Failure {
+            code: u32,
+            #[serde(with = "serde_bytes")]
+            detail: Vec<u8>,
+        }
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Response {
+            #[serde(with = "crate::result")]
+            result: core::result::Result<serde_bytes::ByteBuf, Failure>,
+        }
+
+        let ok = Response {
+            result: Ok(serde_bytes::ByteBuf::from(vec![0x01, 0x02])),
+        };
+        let err = Response {
+            result: Err(Failure {
+                code: 7,
+                detail: vec![0xff],
+            }),
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        assert_eq!(config.result_repr(), ResultRepr::Tagged);
+        let json = crate::to_string(&ok, &config).unwrap();
+        assert_eq!(json, r#"{"result":{"Ok":"0x0102"}}"#);
+        assert_eq!(from_str::<Response>(&json, &config).unwrap(), ok);
+        let json = crate::to_string(&err, &config).unwrap();
+        assert_eq!(json, r#"{"result":{"Err":{"code":7,"detail":"0xff"}}}"#);
+        assert_eq!(from_str::<Response>(&json, &config).unwrap(), err);
+
+        let config = config.set_result_repr(ResultRepr::OkError);
+        let json = crate::to_string(&ok, &config).unwrap();
+        assert_eq!(json, r#"{"result":{"ok":"0x0102","error":null}}"#);
+        assert_eq!(from_str::<Response>(&json, &config).unwrap(), ok);
+        let json = crate::to_string(&err, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"result":{"ok":null,"error":{"code":7,"detail":"0xff"}}}"#
+        );
+        assert_eq!(from_str::<Response>(&json, &config).unwrap(), err);
+
+        // Missing fields count as null
+        let json = r#"{"result":{"ok":"0x0102"}}"#;
+        assert_eq!(from_str::<Response>(json, &config).unwrap(), ok);
+        assert!(from_str::<Response>(r#"{"result":"0x0102"}"#, &config).is_err());
+        assert!(from_str::<Response>(r#"{"result":{"ok":"zz"}}"#, &config).is_err());
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_from_gzip_reader() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            name: String,
+        }
+
+        fn gzip(input: &[u8]) -> Vec<u8> {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(input).unwrap();
+            encoder.finish().unwrap()
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x01, 0x02, 0xff],
+            name: "test".into(),
+        };
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let json = crate::to_vec(&test_data, &config).unwrap();
+        let compressed = gzip(&json);
+
+        let result: TestStruct = from_gzip_reader(compressed.as_slice(), &config).unwrap();
+        assert_eq!(result, test_data);
+
+        // Concatenated members read as one stream
+        let (head, tail) = json.split_at(json.len() / 2);
+        let mut members = gzip(head);
+        members.extend(gzip(tail));
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result: TestStruct = from_gzip_reader(members.as_slice(), &config).unwrap();
+        assert_eq!(result, test_data);
+
+        // The size limit counts decompressed bytes
+        let config = config.set_max_input_bytes(Some(json.len() - 1));
+        let result: Result<TestStruct> = from_gzip_reader(compressed.as_slice(), &config);
+        assert!(matches!(result, Err(crate::Error::InputTooLarge { .. })));
+
+        let result: Result<TestStruct> = from_gzip_reader(json.as_slice(), &config);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_str_recovering() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            first: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            good: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            second: Vec<u8>,
+            name: String,
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let json = r#"{"first":"0x0g","good":"0x0102","second":"0x123","name":"form"}"#;
+
+        let (result, errors) = from_str_recovering::<TestStruct>(json, &config).unwrap();
+        assert_eq!(
+            result,
+            TestStruct {
+                first: vec![],
+                good: vec![0x01, 0x02],
+                second: vec![],
+                name: "form".into(),
+            }
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            Error::HexDecode {
+                error: hex::FromHexError::InvalidHexCharacter { c: 'g', index: 3 },
+                ..
+            }
+        ));
+        assert!(matches!(
+            errors[1],
+            Error::HexDecode {
+                error: hex::FromHexError::OddLength,
+                ..
+            }
+        ));
+
+        // Outside the recovering entry point the first error fails the call
+        let result: Result<TestStruct> = from_str(json, &config);
+        assert!(matches!(result, Err(Error::HexDecode { .. })));
+
+        // Errors other than byte decoding are not recovered
+        let json = r#"{"first":1,"good":"0x","second":"0x","name":"form"}"#;
+        assert!(from_str_recovering::<TestStruct>(json, &config).is_err());
+    }
+
+    #[test]
+    fn test_from_str_trailing_data() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            a: i32,
+        }
+
+        let json = r#"{"a":1} garbage"#;
+        let result: Result<TestStruct> = from_str(json, &Config::default());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("trailing characters")
+        );
+
+        let config = Config::default().enable_trailing_data();
+        let result: TestStruct = from_str(json, &config).unwrap();
+        assert_eq!(result, TestStruct { a: 1 });
+        let result: TestStruct = from_slice(br#"{"a":2}{"a":3}"#, &config).unwrap();
+        assert_eq!(result, TestStruct { a: 2 });
+    }
+
+    #[test]
+    fn test_max_depth_boundary() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Tree(Vec<Tree>);
+
+        fn nested(depth: usize) -> String {
+            format!("{}{}", "[".repeat(depth), "]".repeat(depth))
+        }
+
+        let config = Config::default().set_max_depth(Some(3));
+
+        let result: Tree = from_str(&nested(3), &config).unwrap();
+        assert_eq!(result, Tree(vec![Tree(vec![Tree(vec![])])]));
+
+        let result: Result<Tree> = from_str(&nested(4), &config);
+        assert!(result.is_err());
+
+        let value: serde_json::Value = serde_json::from_str(&nested(4)).unwrap();
+        let result: Result<Tree> = from_value(value, &config);
+        assert!(result.is_err());
+
+        // A deserializer starting below the top counts its own depth
+        let value: serde_json::Value = serde_json::from_str(&nested(2)).unwrap();
+        assert!(Tree::deserialize(Deserializer::with_depth(value.clone(), &config, 1)).is_ok());
+        assert!(Tree::deserialize(Deserializer::with_depth(value, &config, 2)).is_err());
+
+        let result: Result<Tree> = from_str(&nested(129), &Config::default());
+        assert!(result.unwrap_err().to_string().contains("recursion limit"));
+    }
+
+    #[test]
+    fn test_max_string_len() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            name: String,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_max_string_len(Some(10));
+
+        let result: TestStruct =
+            from_str(r#"{"name":"abc","data":"0x01020304"}"#, &config).unwrap();
+        assert_eq!(result.data, vec![1, 2, 3, 4]);
+
+        let json = format!(r#"{{"name":"abc","data":"0x{}"}}"#, "ab".repeat(64));
+        let result: Result<TestStruct> = from_str(&json, &config);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("exceeds the maximum length of 10")
+        );
+
+        let result: Result<TestStruct> =
+            from_str(r#"{"name":"abcdefghijk","data":"0x01"}"#, &config);
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("string of 11 bytes")
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_round_trip_sets_of_bytes() {
+        use serde_bytes::{ByteArray, ByteBuf};
+        use std::collections::{BTreeSet, HashSet};
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let set: HashSet<ByteBuf> =
+            HashSet::from([ByteBuf::from(vec![0x01, 0x02]), ByteBuf::from(vec![0xff])]);
+        let json = crate::to_string(&set, &config).unwrap();
+        assert!(json == r#"["0x0102","0xff"]"# || json == r#"["0xff","0x0102"]"#);
+        let result: HashSet<ByteBuf> = from_str(&json, &config).unwrap();
+        assert_eq!(result, set);
+
+        let set: BTreeSet<ByteArray<4>> = BTreeSet::from([
+            ByteArray::new([0xde, 0xad, 0xbe, 0xef]),
+            ByteArray::new([0, 0, 0, 1]),
+        ]);
+        let json = crate::to_string(&set, &config).unwrap();
+        assert_eq!(json, r#"["0x00000001","0xdeadbeef"]"#);
+        let result: BTreeSet<ByteArray<4>> = from_str(&json, &config).unwrap();
+        assert_eq!(result, set);
+
+        // Elements without a bytes wrapper are ordinary sequences of numbers
+        let set: BTreeSet<Vec<u8>> = BTreeSet::from([vec![1, 2]]);
+        let json = crate::to_string(&set, &config).unwrap();
+        assert_eq!(json, "[[1,2]]");
+        let result: BTreeSet<Vec<u8>> = from_str(&json, &config).unwrap();
+        assert_eq!(result, set);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_rlp() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_rlp().enable_hex_prefix();
+
+        let long = vec![0xaa; 56];
+        let long_hex = format!("0xb838{}", "aa".repeat(56));
+        let cases = vec![
+            (vec![], "0x80".to_string()),
+            (vec![0x0f], "0x0f".to_string()),
+            (vec![0x80], "0x8180".to_string()),
+            (b"dog".to_vec(), "0x83646f67".to_string()),
+            (long, long_hex),
+        ];
+
+        for (data, expected) in cases {
+            let test_data = TestStruct { data };
+            let json = crate::to_string(&test_data, &config).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{}"}}"#, expected));
+
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, test_data);
+        }
+
+        let result: Result<TestStruct> = from_str(r#"{"data":"0xc0"}"#, &config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_bytes_protobuf() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_protobuf();
+
+        // 127 is the longest length that fits in one varint byte
+        let cases = vec![
+            (vec![], "00".to_string()),
+            (vec![0x01, 0x02], "020102".to_string()),
+            (vec![0xaa; 127], format!("7f{}", "aa".repeat(127))),
+            (vec![0xaa; 128], format!("8001{}", "aa".repeat(128))),
+            (vec![0xaa; 300], format!("ac02{}", "aa".repeat(300))),
+        ];
+
+        for (data, expected) in cases {
+            let test_data = TestStruct { data };
+            let json = crate::to_string(&test_data, &config).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{}"}}"#, expected));
+
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, test_data);
+        }
+
+        let config = config.enable_hex_prefix();
+        let json = crate::to_string(&TestStruct { data: vec![0xff] }, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0x01ff"}"#);
+
+        for invalid in [
+            // Empty, and a varint cut off after its continuation bit
+            String::new(),
+            "80".to_string(),
+            // Overlong encoding of 0
+            "8000".to_string(),
+            // Length 128 with 127 bytes, and length 127 with 128 bytes
+            format!("8001{}", "aa".repeat(127)),
+            format!("7f{}", "aa".repeat(128)),
+        ] {
+            let json = format!(r#"{{"data":"{}"}}"#, invalid);
+            let result: Result<TestStruct> = from_str(&json, &config);
+            assert!(result.is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_from_str_base32_case_insensitive() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: b"foobar".to_vec(),
+        };
+
+        let config = Config::default().set_bytes_base32();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"MZXW6YTBOI======"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let lowercase = r#"{"data":"mzxw6ytboi======"}"#;
+        let result: Result<TestStruct> = from_str(lowercase, &config);
+        assert!(result.is_err());
+
+        let config = config.enable_bytes_case_insensitive_decode();
+        let result: TestStruct = from_str(lowercase, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: TestStruct = from_str(r#"{"data":"mZxW6yTbOi======"}"#, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_zbase32() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_zbase32();
+
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            (vec![], ""),
+            (vec![0], "yy"),
+            (b"hello".to_vec(), "pb1sa5dx"),
+            (b"hello world".to_vec(), "pb1sa5dxrb5s6hucco"),
+            (vec![1, 2, 3, 4, 5, 6, 7], "yrbygbyfyado"),
+        ];
+
+        for (data, expected) in cases {
+            let test_data = TestStruct { data };
+            let json = crate::to_string(&test_data, &config).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{}"}}"#, expected));
+
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, test_data);
+        }
+
+        let uppercase = r#"{"data":"PB1SA5DX"}"#;
+        let result: Result<TestStruct> = from_str(uppercase, &config);
+        assert!(result.is_err());
+        let config = config.enable_bytes_case_insensitive_decode();
+        let result: TestStruct = from_str(uppercase, &config).unwrap();
+        assert_eq!(result.data, b"hello");
+
+        // RFC 4648 padding is not part of z-base-32
+        let result: Result<TestStruct> = from_str(r#"{"data":"yy======"}"#, &config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_bytes_data_uri() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x89, b'P', b'N', b'G'],
+        };
+
+        let config = Config::default().set_bytes_data_uri("application/octet-stream");
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"data:application/octet-stream;base64,iVBORw=="}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = config.set_bytes_data_uri("image/png");
+        assert_eq!(config.data_uri_mime_type(), "image/png");
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"data:image/png;base64,iVBORw=="}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let empty = TestStruct { data: vec![] };
+        let json = crate::to_string(&empty, &config).unwrap();
+        assert_eq!(json, r#"{"data":"data:image/png;base64,"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, empty);
+
+        // Any media type with parameters, and the scheme in any case
+        let result: TestStruct = from_str(
+            r#"{"data":"DATA:text/plain;charset=utf-8;base64,iVBORw=="}"#,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, test_data);
+
+        for invalid in [
+            r#"{"data":"iVBORw=="}"#,
+            r#"{"data":"http:image/png;base64,iVBORw=="}"#,
+            r#"{"data":"data:image/png,iVBORw=="}"#,
+            r#"{"data":"data:image/png;base64"}"#,
+            r#"{"data":"data:image/png;base64,!!"}"#,
+            r#"{"data":"data:image/png;base64,iVBORw"}"#,
+            r#"{"data":"data:image/png;base64,-_8="}"#,
+        ] {
+            let result: Result<TestStruct> = from_str(invalid, &config);
+            assert!(result.is_err(), "{}", invalid);
+        }
+
+        // The base64 decoding options apply to the data
+        let config = config
+            .disable_base64_strict_padding()
+            .enable_base64_accept_both_alphabets()
+            .enable_base64_length_validation();
+        let result: TestStruct =
+            from_str(r#"{"data":"data:image/png;base64,iVBORw"}"#, &config).unwrap();
+        assert_eq!(result, test_data);
+        let result: TestStruct =
+            from_str(r#"{"data":"data:image/png;base64,-_8="}"#, &config).unwrap();
+        assert_eq!(result.data, vec![0xfb, 0xff]);
+        let err = from_str::<TestStruct>(r#"{"data":"data:image/png;base64,iVBOR"}"#, &config)
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid base64 length 5"));
+    }
+
+    #[test]
+    fn test_round_trip_bytes_empty_placeholder() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let empty = TestStruct { data: vec![] };
+        let zero = TestStruct { data: vec![0x00] };
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .set_bytes_empty_placeholder("empty");
+        assert_eq!(config.bytes_empty_placeholder(), Some("empty"));
+        let json = crate::to_string(&empty, &config).unwrap();
+        assert_eq!(json, r#"{"data":"empty"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, empty);
+        let json = crate::to_string(&zero, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0x00"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, zero);
+
+        // A placeholder that is also the encoding of `[0x00]` keeps empty
+        // bytes round-tripping but refuses to write `[0x00]`
+        let config = config.set_bytes_empty_placeholder("0x00");
+        let json = crate::to_string(&empty, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0x00"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, empty);
+        assert!(crate::to_string(&zero, &config).is_err());
+        let result: TestStruct = from_str(r#"{"data":"0x0000"}"#, &config).unwrap();
+        assert_eq!(result.data, vec![0x00, 0x00]);
+
+        // Without the placeholder, "0x00" is a single zero byte again
+        let config = config.clear_bytes_empty_placeholder();
+        let result: TestStruct = from_str(r#"{"data":"0x00"}"#, &config).unwrap();
+        assert_eq!(result, zero);
+        assert_eq!(
+            crate::to_string(&empty, &config).unwrap(),
+            r#"{"data":"0x"}"#
+        );
+    }
+
+    #[test]
+    fn test_round_trip_bytes_uuencode() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_uuencode();
+
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            (vec![], r"`\n"),
+            (b"Cat".to_vec(), r"#0V%T\n`\n"),
+            (
+                b"http://www.wikipedia.org\r\n".to_vec(),
+                r"::'1T<#HO+W=W=RYW:6MI<&5D:6$N;W)G#0H`\n`\n",
+            ),
+        ];
+
+        for (data, expected) in cases {
+            let test_data = TestStruct { data };
+            let json = crate::to_string(&test_data, &config).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{}"}}"#, expected));
+
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, test_data);
+        }
+
+        let data: Vec<u8> = (0..=255).collect();
+        let test_data = TestStruct { data };
+        let json = crate::to_string(&test_data, &config).unwrap();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        // Spaces for zero and CRLF line endings
+        let result: TestStruct = from_str(r#"{"data":"!80  \r\n \r\n"}"#, &config).unwrap();
+        assert_eq!(result.data, b"a");
+
+        let result: Result<TestStruct> = from_str(r##"{"data":"#0V"}"##, &config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_bytes_base36() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_base36();
+
+        let cases: Vec<(Vec<u8>, &str)> = vec![
+            (vec![], ""),
+            (vec![0], "0"),
+            (vec![0, 0, 0], "000"),
+            (vec![0, 0, 1, 0], "0074"),
+            (vec![0xff; 4], "1z141z3"),
+            (b"hello".to_vec(), "5pzcszu7"),
+        ];
+
+        for (data, expected) in cases {
+            let test_data = TestStruct { data };
+            let json = crate::to_string(&test_data, &config).unwrap();
+            assert_eq!(json, format!(r#"{{"data":"{}"}}"#, expected));
+
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, test_data);
+        }
+
+        let uppercase = r#"{"data":"005PZCSZU7"}"#;
+        let result: Result<TestStruct> = from_str(uppercase, &config);
+        assert!(result.is_err());
+
+        let config = config.enable_bytes_case_insensitive_decode();
+        let result: TestStruct = from_str(uppercase, &config).unwrap();
+        assert_eq!(result.data, b"\0\0hello");
+    }
+
+    #[test]
+    fn test_from_str_bytes_utf8() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_utf8();
+        let result: TestStruct = from_str(r#"{"data":"Hello"}"#, &config).unwrap();
+        assert_eq!(result.data, [72, 101, 108, 108, 111]);
+
+        let json = crate::to_string(&result, &config).unwrap();
+        assert_eq!(json, r#"{"data":"Hello"}"#);
+
+        #[derive(Deserialize)]
+        struct Borrowed<'a> {
+            #[serde(borrow, with = "serde_bytes")]
+            data: &'a [u8],
+        }
+
+        let result: Borrowed = from_str(r#"{"data":"héllo"}"#, &config).unwrap();
+        assert_eq!(result.data, "héllo".as_bytes());
+
+        let config = config.set_bytes_prefix("s:");
+        let result: TestStruct = from_str(r#"{"data":"s:h\u00e9"}"#, &config).unwrap();
+        assert_eq!(result.data, "hé".as_bytes());
+
+        let invalid = TestStruct {
+            data: vec![0xff, 0xfe],
+        };
+        assert!(crate::to_string(&invalid, &config).is_err());
+    }
+
+    #[cfg(feature = "arrayvec")]
+    #[test]
+    fn test_round_trip_array_vec_bytes() {
+        use arrayvec::ArrayVec;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::array_vec_bytes")]
+            data: ArrayVec<u8, 8>,
+        }
+
+        let test_data = TestStruct {
+            data: [0x01, 0x02, 0xff].into_iter().collect(),
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0x0102ff"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: TestStruct = from_str(r#"{"data":"0x0102030405060708"}"#, &config).unwrap();
+        assert_eq!(result.data.as_slice(), [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let long = r#"{"data":"0x010203040506070809"}"#;
+        let err = from_str::<TestStruct>(long, &config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("invalid length 9, expected at most 8 bytes")
+        );
+
+        let config = Config::default();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":[1,2,255]}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_round_trip_small_vec_bytes() {
+        use smallvec::SmallVec;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::small_vec_bytes")]
+            data: SmallVec<[u8; 4]>,
+        }
+
+        let test_data = TestStruct {
+            data: SmallVec::from_slice(&[0x01, 0x02, 0x03, 0x04, 0x05]),
+        };
+
+        let config = Config::default().set_bytes_base64();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":"AQIDBAU="}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_from_str_base64_bytes_array() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::bytes_array")]
+            id: [u8; 16],
+        }
+
+        let test_data = TestStruct {
+            id: *b"0123456789abcdef",
+        };
+
+        let config = Config::default().set_bytes_base64();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"id":"MDEyMzQ1Njc4OWFiY2RlZg=="}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        // Unpadded input needs relaxed padding
+        let unpadded = r#"{"id":"MDEyMzQ1Njc4OWFiY2RlZg"}"#;
+        assert!(from_str::<TestStruct>(unpadded, &config).is_err());
+        let config = config.disable_base64_strict_padding();
+        let result: TestStruct = from_str(unpadded, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = Config::default()
+            .set_bytes_base64_url_safe()
+            .disable_base64_strict_padding();
+        let result: TestStruct = from_str(r#"{"id":"MDEyMzQ1Njc4OWFiY2RlZg"}"#, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let short = r#"{"id":"MDEyMzQ1Njc4OWFiY2Rl"}"#;
+        let err = from_str::<TestStruct>(short, &config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("invalid length 15, expected 16 bytes")
+        );
+
+        let long = r#"{"id":"MDEyMzQ1Njc4OWFiY2RlZmc="}"#;
+        assert!(from_str::<TestStruct>(long, &config).is_err());
+
+        let config = Config::default();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_from_str_force_sign() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            a: i32,
+            b: i64,
+            c: u64,
+        }
+
+        let config = Config::default().enable_force_sign();
+
+        let result: TestStruct = from_str(r#"{"a":"+5","b":"-5","c":7}"#, &config).unwrap();
+        assert_eq!(result, TestStruct { a: 5, b: -5, c: 7 });
+
+        let test_data = TestStruct {
+            a: -1,
+            b: 0,
+            c: u64::MAX,
+        };
+        let json = crate::to_string(&test_data, &config).unwrap();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: Result<TestStruct> = from_str(r#"{"a":"+5","b":"-5","c":"-7"}"#, &config);
+        assert!(result.is_err());
+
+        let result: Result<TestStruct> = from_str(r#"{"a":"+","b":"-5","c":7}"#, &config);
+        assert!(result.is_err());
+
+        let result: Result<TestStruct> = from_str(r#"{"a":"+0x5","b":"-5","c":7}"#, &config);
+        assert!(result.is_err());
+
+        let config = config.enable_hex_numbers();
+        let result: TestStruct = from_str(r#"{"a":"+0x5","b":"-0x5","c":"0x7"}"#, &config).unwrap();
+        assert_eq!(result, TestStruct { a: 5, b: -5, c: 7 });
+    }
+
+    #[test]
+    fn test_from_str_numbers_as_strings() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            a: i32,
+            b: u64,
+            c: f64,
+            d: f32,
+        }
+
+        let test_data = TestStruct {
+            a: -5,
+            b: u64::MAX,
+            c: 1.0,
+            d: 2.5,
+        };
+
+        let config = Config::default().enable_numbers_as_strings();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"a":"-5","b":"18446744073709551615","c":"1.0","d":"2.5"}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        // Plain numbers are still accepted
+        let result: TestStruct = from_str(
+            r#"{"a":-5,"b":18446744073709551615,"c":1,"d":2.5}"#,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(result, test_data);
+
+        let result: Result<TestStruct> =
+            from_str(r#"{"a":"5.5","b":"1","c":"1","d":"1"}"#, &config);
+        assert!(result.is_err());
+        let result: Result<TestStruct> =
+            from_str(r#"{"a":"5","b":"1","c":"NaN","d":"1"}"#, &config);
+        assert!(result.is_err());
+
+        let config = config.disable_numbers_as_strings();
+        let result: Result<TestStruct> = from_str(&json, &config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_str_error_variants() {
+        use crate::Error;
+
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            #[allow(dead_code)]
+            data: Vec<u8>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct ArrayStruct {
+            #[serde(with = "crate::bytes_array")]
+            #[allow(dead_code)]
+            data: [u8; 4],
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let err = from_str::<TestStruct>(r#"{"data":"0x123"}"#, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::HexDecode {
+                error: hex::FromHexError::OddLength,
+                ..
+            }
+        ));
+
+        let err = from_str::<TestStruct>(r#"{"data":"0xzz"}"#, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::HexDecode {
+                error: hex::FromHexError::InvalidHexCharacter { c: 'z', index: 2 },
+                ..
+            }
+        ));
+
+        // The array's own length check is reported by serde
+        let err = from_str::<ArrayStruct>(r#"{"data":"0x0102"}"#, &config).unwrap_err();
+        assert!(matches!(err, Error::Json(_)));
+        assert_eq!(
+            err.to_string(),
+            "invalid length 2, expected 4 bytes at line 1 column 16"
+        );
+
+        let err = from_str::<TestStruct>(r#"{"data":"0x12"#, &config).unwrap_err();
+        assert!(matches!(err, Error::Json(_)));
+
+        let config = Config::default().set_bytes_base64();
+        let err = from_value::<TestStruct>(json!({"data": "A"}), &config).unwrap_err();
+        assert!(matches!(err, Error::Base64Decode { .. }));
+
+        let err: serde_json::Error = err.into();
+        assert!(err.to_string().starts_with("invalid base64 string"));
+    }
+
+    #[test]
+    fn test_from_str_error_not_taken_from_earlier_attempt() {
+        use crate::Error;
+        use serde::de::Error as _;
+
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        fn reject<'de, D>(_: D) -> core::result::Result<u8, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Err(D::Error::custom("invalid base64 string: Invalid padding"))
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Checked {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(deserialize_with = "reject")]
+            n: u8,
+        }
+
+        // The base64 attempt fails on the unpadded "0x1234" before hex succeeds
+        let config = Config::default()
+            .enable_hex_prefix()
+            .set_bytes_decode_order(vec![
+                BytesFormat::Base64(crate::Base64Variant::STANDARD),
+                BytesFormat::Hex,
+            ]);
+        let result: TestStruct = from_str(r#"{"data":"0x1234"}"#, &config).unwrap();
+        assert_eq!(result.data, vec![0x12, 0x34]);
+
+        // A later, unrelated error with the same text stays a JSON error
+        let err = from_str::<Checked>(r#"{"data":"0x1234","n":1}"#, &config).unwrap_err();
+        assert!(matches!(err, Error::Json(_)), "{:?}", err);
+
+        // The message of a recovered error leaves out the position
+        let config = Config::default().set_bytes_base36();
+        let err = from_str::<TestStruct>("{\n\"data\": \"!\"}", &config).unwrap_err();
+        match err {
+            Error::BytesDecode {
+                format, message, ..
+            } => {
+                assert_eq!(format, BytesFormat::Base36);
+                assert!(!message.contains("line"), "{}", message);
+            }
+            err => panic!("unexpected error {:?}", err),
+        }
+    }
+
+    #[test]
+    fn test_hex_invalid_character_position() {
+        use crate::Error;
+
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            #[allow(dead_code)]
+            data: Vec<u8>,
+        }
+
+        let invalid_at =
+            |json: &str, config: &Config| match from_str::<TestStruct>(json, config).unwrap_err() {
+                Error::HexDecode {
+                    error: hex::FromHexError::InvalidHexCharacter { c, index },
+                    ..
+                } => (c, index),
+                err => panic!("unexpected error: {}", err),
+            };
+
+        let config = Config::default().set_bytes_hex();
+        assert_eq!(invalid_at(r#"{"data":"0x0102g304"}"#, &config), ('g', 6));
+        // Reported ahead of the odd length
+        assert_eq!(invalid_at(r#"{"data":"0x1g3"}"#, &config), ('g', 3));
+        assert_eq!(invalid_at(r#"{"data":"01é2"}"#, &config), ('é', 2));
+
+        let config = config
+            .set_bytes_prefix("hex:")
+            .enable_hex_ignore_whitespace();
+        assert_eq!(
+            invalid_at(r#"{"data":"hex:0x01 02 0z"}"#, &config),
+            ('z', 13)
+        );
+
+        let err = from_str::<TestStruct>(
+            r#"{"data":"0x0102g304"}"#,
+            &Config::default().set_bytes_hex(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid hex string: Invalid character 'g' at position 6 at line 1 column 20"
+        );
+        assert_eq!((err.line(), err.column()), (1, 20));
+    }
+
+    #[test]
+    fn test_round_trip_base64_mime() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: (0..=199).collect(),
+        };
+
+        let config = Config::default().set_bytes_base64_mime();
+        let json = crate::to_string(&test_data, &config).unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let encoded = value["data"].as_str().unwrap();
+        let lines: Vec<&str> = encoded.split("\r\n").collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[..3].iter().all(|line| line.len() == 76));
+        assert_eq!(lines[3].len(), 268 - 3 * 76);
+        assert_eq!(
+            lines.concat(),
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &test_data.data)
+        );
+        assert!(json.contains(r"\r\n"));
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let spaced = json.replace(r"\r\n", r"\n  ");
+        let result: TestStruct = from_str(&spaced, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let short = TestStruct {
+            data: vec![1, 2, 3],
+        };
+        let json = crate::to_string(&short, &config).unwrap();
+        assert_eq!(json, r#"{"data":"AQID"}"#);
+    }
+
+    #[test]
+    fn test_round_trip_vec_bytes() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::vec_bytes")]
+            blobs: Vec<Vec<u8>>,
+        }
+
+        let test_data = TestStruct {
+            blobs: vec![vec![0x01, 0x02], vec![], vec![0xde, 0xad, 0xbe, 0xef]],
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"blobs":["0x0102","0x","0xdeadbeef"]}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = Config::default();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"blobs":[[1,2],[],[222,173,190,239]]}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_default_signed() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x00, 0x7f, 0x80, 0xff],
+        };
+
+        let config = Config::default().enable_bytes_default_signed();
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":[0,127,-128,-1]}"#);
+
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: TestStruct = from_str(r#"{"data":[-1]}"#, &config).unwrap();
+        assert_eq!(result.data, vec![0xff]);
+
+        let result: Result<TestStruct> = from_str(r#"{"data":[128]}"#, &config);
+        assert!(result.is_err());
+
+        let result: Result<TestStruct> = from_str(r#"{"data":[-1]}"#, &Config::default());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_bytes_array_radix() {
+        use crate::BytesArrayRadix;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(with = "crate::bytes_array")]
+            array: [u8; 2],
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x01, 0xff],
+            array: [0x00, 0x0a],
+        };
+
+        let config = Config::default()
+            .enable_bytes_default_signed()
+            .set_bytes_array_radix(BytesArrayRadix::Hex);
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":["0x01","0xff"],"array":["0x00","0x0a"]}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let result: TestStruct =
+            from_str(r#"{"data":["0XFF",1,"0x0"],"array":[0,"0xA"]}"#, &config).unwrap();
+        assert_eq!(result.data, vec![0xff, 0x01, 0x00]);
+        assert_eq!(result.array, [0x00, 0x0a]);
+
+        for invalid in [r#""0x100""#, r#""ff""#, r#""0x+1""#, r#""0o7""#, "256"] {
+            let json = format!(r#"{{"data":[{}],"array":[0,0]}}"#, invalid);
+            let result: Result<TestStruct> = from_str(&json, &config);
+            assert!(result.is_err(), "{}", invalid);
+        }
+
+        let config = config.set_bytes_array_radix(BytesArrayRadix::Octal);
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":["0o001","0o377"],"array":["0o000","0o012"]}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+
+        let config = config.set_bytes_array_radix(BytesArrayRadix::Binary);
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":["0b00000001","0b11111111"],"array":["0b00000000","0b00001010"]}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_hex_as_number_array() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            empty: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x01, 0x02, 0xab],
+            empty: vec![],
+        };
+
+        let config = Config::default()
+            .set_bytes_base64()
+            .set_bytes_hex_as_number_array();
+        assert_eq!(config.bytes_format(), BytesFormat::Default);
+        let json = crate::to_string(&test_data, &config).unwrap();
+        assert_eq!(json, r#"{"data":["0x01","0x02","0xab"],"empty":[]}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, test_data);
+    }
+
+    #[test]
+    fn test_from_str_absent_bytes_field_with_default() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            name: String,
+            #[serde(default, with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(default, with = "serde_bytes")]
+            extra: Option<Vec<u8>>,
+        }
+
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Required {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let result: TestStruct = from_str(r#"{"name":"a"}"#, &config).unwrap();
+        assert_eq!(
+            result,
+            TestStruct {
+                name: "a".to_string(),
+                data: vec![],
+                extra: None,
+            }
+        );
+
+        let result: TestStruct =
+            from_str(r#"{"data":"0x01","name":"a","extra":"0x02"}"#, &config).unwrap();
+        assert_eq!(result.data, vec![1]);
+        assert_eq!(result.extra, Some(vec![2]));
+
+        // Without a default serde reports the field before the config is seen
+        let err = from_str::<Required>("{}", &config).unwrap_err();
+        assert!(err.to_string().contains("missing field `data`"));
+    }
+
+    #[test]
+    fn test_from_str_bytes_visit_method() {
+        use serde::de::{SeqAccess, Visitor};
+
+        #[derive(Debug, PartialEq)]
+        enum Visited {
+            Bytes,
+            ByteBuf,
+            BorrowedBytes,
+            Seq,
+        }
+
+        impl<'de> Deserialize<'de> for Visited {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct RecordingVisitor;
+
+                impl<'de> Visitor<'de> for RecordingVisitor {
+                    type Value = Visited;
+
+                    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        formatter.write_str("bytes")
+                    }
+
+                    fn visit_bytes<E>(self, _: &[u8]) -> core::result::Result<Visited, E> {
+                        Ok(Visited::Bytes)
+                    }
+
+                    fn visit_byte_buf<E>(self, _: Vec<u8>) -> core::result::Result<Visited, E> {
+                        Ok(Visited::ByteBuf)
+                    }
+
+                    fn visit_borrowed_bytes<E>(
+                        self,
+                        _: &'de [u8],
+                    ) -> core::result::Result<Visited, E> {
+                        Ok(Visited::BorrowedBytes)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Visited, A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        while seq.next_element::<u8>()?.is_some() {}
+                        Ok(Visited::Seq)
+                    }
+                }
+
+                deserializer.deserialize_bytes(RecordingVisitor)
+            }
+        }
+
+        let cases = [
+            (
+                Config::default().set_bytes_hex(),
+                r#""0102""#,
+                Visited::ByteBuf,
+            ),
+            (
+                Config::default().set_bytes_base64(),
+                r#""AQI=""#,
+                Visited::ByteBuf,
+            ),
+            (
+                Config::default().set_bytes_base32(),
+                r#""AEBA====""#,
+                Visited::ByteBuf,
+            ),
+            (
+                Config::default().set_bytes_rlp(),
+                r#""820102""#,
+                Visited::Bytes,
+            ),
+            (Config::default(), "[1,2]", Visited::Seq),
+        ];
+
+        for (config, json, expected) in cases {
+            let result: Visited = from_str(json, &config).unwrap();
+            assert_eq!(result, expected);
+        }
+
+        #[derive(Deserialize, Debug)]
+        struct Borrowed<'a> {
+            #[allow(dead_code)]
+            data: &'a [u8],
+        }
+
+        let config = Config::default().set_bytes_hex();
+        let result: Result<Borrowed> = from_str(r#"{"data":"0102"}"#, &config);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_str_bytes_decode_order() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_decode_order(vec![
+            BytesFormat::Hex,
+            BytesFormat::Base64(crate::Base64Variant::STANDARD),
+            BytesFormat::Default,
+        ]);
+
+        for json in [
+            r#"{"data":"0x010203ff"}"#,
+            r#"{"data":"AQID/w=="}"#,
+            r#"{"data":[1,2,3,255]}"#,
+        ] {
+            let result: TestStruct = from_str(json, &config).unwrap();
+            assert_eq!(result.data, vec![1, 2, 3, 255]);
+        }
+
+        let err = from_str::<TestStruct>(r#"{"data":"!"}"#, &config).unwrap_err();
+        let message = err.to_string();
+        assert!(message.starts_with("no bytes format matched (hex: "));
+        assert!(message.contains("; base64: "));
+        assert!(message.contains("; array: "));
+    }
+
+    #[test]
+    fn test_round_trip_hex_group_prefix() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![0xaa, 0xbb, 0x01],
+        };
+
+        let config = Config::default().set_bytes_hex_group_prefix(1);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0xaa0xbb0x01"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = Config::default().set_bytes_hex_group_prefix(2);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0xaabb0x01"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let empty = TestStruct { data: vec![] };
+        let config = Config::default().set_bytes_hex_group_prefix(1);
+        let json = crate::to_string(&empty, &config).unwrap();
+        assert_eq!(json, r#"{"data":""}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, empty);
+    }
+
+    #[test]
+    fn test_round_trip_hex_separator() {
+        use crate::Error;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![0xab, 0xc0, 0x12],
+        };
+
+        let config = Config::default().set_bytes_separator_every_nibble("-");
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"a-b-c-0-1-2"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = config.enable_hex_prefix();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0xa-b-c-0-1-2"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Groups of three digits split bytes
+        let config = Config::default().set_bytes_hex_separator(" : ", 3);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"abc : 012"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+        let result: TestStruct = from_str(r#"{"data":"ab : c0 : 12"}"#, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = Config::default().set_bytes_separator_every_nibble("-");
+        let err = from_str::<TestStruct>(r#"{"data":"a-b-c-g-1"}"#, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::HexDecode {
+                error: hex::FromHexError::InvalidHexCharacter { c: 'g', index: 6 },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_from_str_bytes() {
+        let config = Config::default().set_bytes_hex();
+        assert_eq!(
+            from_str_bytes(r#""0x0102ff""#, &config).unwrap(),
+            vec![1, 2, 255]
+        );
+        assert!(from_str::<Vec<u8>>(r#""0x0102ff""#, &config).is_err());
+        assert_eq!(
+            from_str::<Vec<u8>>("[1,2,255]", &config).unwrap(),
+            vec![1, 2, 255]
+        );
+
+        let config = Config::default();
+        assert_eq!(
+            from_str_bytes("[1,2,255]", &config).unwrap(),
+            vec![1, 2, 255]
+        );
+    }
+
+    #[test]
+    fn test_from_str_base64_accept_both_alphabets() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let json = r#"{"data":"AQID_w=="}"#;
+
+        let strict = Config::default().set_bytes_base64();
+        assert!(from_str::<TestStruct>(json, &strict).is_err());
+
+        let config = strict.enable_base64_accept_both_alphabets();
+        let result: TestStruct = from_str(json, &config).unwrap();
+        assert_eq!(result.data, vec![1, 2, 3, 255]);
+        let result: TestStruct = from_str(r#"{"data":"AQID/w=="}"#, &config).unwrap();
+        assert_eq!(result.data, vec![1, 2, 3, 255]);
+
+        let config = Config::default()
+            .set_bytes_base64_url_safe()
+            .enable_base64_accept_both_alphabets()
+            .disable_base64_strict_padding();
+        let result: TestStruct = from_str(r#"{"data":"AQID/w"}"#, &config).unwrap();
+        assert_eq!(result.data, vec![1, 2, 3, 255]);
+        assert_eq!(
+            crate::to_string(&serde_bytes::Bytes::new(&result.data), &config).unwrap(),
+            r#""AQID_w==""#
+        );
+    }
+
+    #[test]
+    fn test_round_trip_mongo_extended() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            name: String,
+        }
+
+        let value = TestStruct {
+            data: b"Hello".to_vec(),
+            name: "test".to_string(),
+        };
+
+        let config = Config::default().set_bytes_mongo_extended();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":{"$binary":{"base64":"SGVsbG8=","subType":"00"}},"name":"test"}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let sample = r#"{"data":{"$binary":{"base64":"SGVsbG8=","subType":"04"}},"name":"test"}"#;
+        let result: TestStruct = from_str(sample, &config).unwrap();
+        assert_eq!(result, value);
+
+        let sample = r#"{"data":{"$binary":{"base64":"SGVsbG8="}},"name":"test"}"#;
+        let result: TestStruct = from_str(sample, &config).unwrap();
+        assert_eq!(result, value);
+
+        let sample = r#"{"data":{"$binary":{"base64":"!!","subType":"00"}},"name":"test"}"#;
+        let err = from_str::<TestStruct>(sample, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::Base64Decode { .. }));
+    }
+
+    #[test]
+    fn test_round_trip_bytes_empty_format() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            full: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            empty: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            full: vec![1, 2],
+            empty: vec![],
+        };
+
+        let config = Config::default()
+            .set_bytes_base64()
+            .set_bytes_empty_format(BytesFormat::Default);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"full":"AQI=","empty":[]}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let result: TestStruct = from_str(r#"{"full":"AQI=","empty":""}"#, &config).unwrap();
+        assert_eq!(result, value);
+
+        let err = from_str::<TestStruct>(r#"{"full":[1,2],"empty":[]}"#, &config).unwrap_err();
+        assert!(err.to_string().contains("expected a base64 string"));
+
+        let err = from_str::<TestStruct>(r#"{"full":"!","empty":[]}"#, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::Base64Decode { .. }));
+    }
+
+    #[test]
+    fn test_round_trip_bytes_codec() {
+        use crate::ByteCodec;
+
+        /// Hex with every digit rotated by 8 places, as a stand-in custom codec
+        #[derive(Debug)]
+        struct RotHex;
+
+        fn rotate(s: &str) -> String {
+            const DIGITS: &[u8] = b"0123456789abcdef";
+            s.bytes()
+                .map(|b| match DIGITS.iter().position(|&d| d == b) {
+                    Some(i) => DIGITS[(i + 8) % 16] as char,
+                    None => b as char,
+                })
+                .collect()
+        }
+
+        impl ByteCodec for RotHex {
+            fn encode(&self, input: &[u8]) -> String {
+                rotate(&hex::encode(input))
+            }
+
+            fn decode(&self, input: &str) -> core::result::Result<Vec<u8>, String> {
+                hex::decode(rotate(input)).map_err(|e| e.to_string())
+            }
+        }
+
+        static ROT_HEX: RotHex = RotHex;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![0x01, 0x9f],
+        };
+
+        let config = Config::default().set_bytes_codec(&ROT_HEX);
+        assert!(config.validate().is_ok());
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"8917"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let err = from_str::<TestStruct>(r#"{"data":"xyz"}"#, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::BytesDecode {
+                format: BytesFormat::Codec(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_length_prefixed() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![1, 2, 255],
+        };
+
+        let config = Config::default().set_bytes_length_prefixed();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":[3,1,2,255]}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let json = crate::to_string(&TestStruct { data: vec![] }, &config).unwrap();
+        assert_eq!(json, r#"{"data":[0]}"#);
+
+        let err = from_str::<TestStruct>(r#"{"data":[4,1,2,255]}"#, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::LengthMismatch {
+                expected: 4,
+                actual: 3,
+                ..
+            }
+        ));
+
+        assert!(from_str::<TestStruct>(r#"{"data":[]}"#, &config).is_err());
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct ArrayStruct {
+            #[serde(with = "crate::bytes_array")]
+            data: [u8; 2],
+        }
+
+        let json = crate::to_string(&ArrayStruct { data: [7, 8] }, &config).unwrap();
+        assert_eq!(json, r#"{"data":[2,7,8]}"#);
+        let result: ArrayStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result.data, [7, 8]);
+    }
+
+    #[test]
+    fn test_round_trip_hex_reverse() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![0x01, 0x02, 0x03],
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_reverse();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"030201"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = config.enable_hex_prefix();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"0x030201"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_round_trip_node_buffer() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![1, 2, 3],
+        };
+
+        let config = Config::default().set_bytes_node_buffer();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":{"type":"Buffer","data":[1,2,3]}}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let json = r#"{"data":{"data":[1,2,3],"type":"Buffer"}}"#;
+        let result: TestStruct = from_str(json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let json = r#"{"data":{"type":"Uint8Array","data":[1,2,3]}}"#;
+        let err = from_str::<TestStruct>(json, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::BytesDecode {
+                format: BytesFormat::NodeBuffer,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_reader_max_input_bytes() {
+        use std::io::Read as _;
+
+        let json = r#"[1,2,3]"#;
+        let config = Config::default().set_max_input_bytes(Some(json.len()));
+        assert_eq!(config.max_input_bytes(), Some(7));
+        let result: Vec<u8> = from_reader(json.as_bytes(), &config).unwrap();
+        assert_eq!(result, [1, 2, 3]);
+
+        // An endless stream of array elements
+        let endless = std::io::repeat(b'1').take(u64::MAX);
+        let reader = b"[".chain(endless);
+        let config = Config::default().set_max_input_bytes(Some(1024));
+        let err = from_reader::<_, Vec<u8>>(reader, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::InputTooLarge { limit: 1024 }));
+        assert_eq!(err.to_string(), "input exceeds the limit of 1024 bytes");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_round_trip_duration_millis() {
+        use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::time::duration::millis")]
+            timeout: Duration,
+            #[serde(with = "crate::time::duration::nanos_str")]
+            elapsed: Duration,
+            #[serde(with = "crate::time::system_time::secs")]
+            created: SystemTime,
+        }
+
+        let value = TestStruct {
+            timeout: Duration::from_millis(1500),
+            elapsed: Duration::new(1, 5),
+            created: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+        };
+
+        let config = Config::default();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"timeout":1500,"elapsed":"1000000005","created":1700000000}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Sub-millisecond precision is truncated
+        let value = TestStruct {
+            timeout: Duration::from_micros(2999),
+            ..value
+        };
+        let json = crate::to_string(&value, &config).unwrap();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result.timeout, Duration::from_millis(2));
+
+        let value = TestStruct {
+            created: UNIX_EPOCH - Duration::from_secs(1),
+            ..value
+        };
+        assert!(crate::to_string(&value, &config).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_base64_crypt() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            salt: Vec<u8>,
+        }
+
+        // Salt of the bcrypt hash $2a$10$N9qo8uLOickgx2ZMRZoMyeIjZAgcfl7p92ldGxad68LJZdL17lhWy
+        let value = TestStruct {
+            salt: vec![
+                0x3f, 0xfb, 0x2a, 0xfb, 0x03, 0x50, 0x91, 0xe9, 0xa2, 0xcf, 0x86, 0xce, 0x4d, 0xba,
+                0x8e, 0xd2,
+            ],
+        };
+
+        let config = Config::default().set_bytes_base64_crypt();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"salt":"N9qo8uLOickgx2ZMRZoMye"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Padding and the standard alphabet are rejected
+        assert!(from_str::<TestStruct>(r#"{"salt":"N9qo8uLOickgx2ZMRZoMye=="}"#, &config).is_err());
+        let config = config.enable_base64_accept_both_alphabets();
+        assert!(from_str::<TestStruct>(r#"{"salt":"AQID+w"}"#, &config).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_round_trip_map_entries() {
+        use std::collections::{BTreeMap, HashMap};
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::map_entries")]
+            addrs: HashMap<[u8; 4], u32>,
+            #[serde(with = "crate::map_entries")]
+            keys: BTreeMap<serde_bytes::ByteBuf, serde_bytes::ByteBuf>,
+        }
+
+        let value = TestStruct {
+            addrs: HashMap::from([([127, 0, 0, 1], 80)]),
+            keys: BTreeMap::from([(
+                serde_bytes::ByteBuf::from(vec![0x01, 0x02]),
+                serde_bytes::ByteBuf::from(vec![0xff]),
+            )]),
+        };
+
+        let config = Config::default();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"addrs":[[[127,0,0,1],80]],"keys":[[[1,2],[255]]]}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"addrs":[[[127,0,0,1],80]],"keys":[["0x0102","0xff"]]}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let json = r#"{"addrs":[[[10,0,0,1],1],[[10,0,0,2],2]],"keys":[]}"#;
+        let result: TestStruct = from_str(json, &config).unwrap();
+        assert_eq!(result.addrs[&[10, 0, 0, 2]], 2);
+        assert_eq!(result.addrs.len(), 2);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_str_overridden_config() {
+        use std::sync::LazyLock;
+
+        static BASE64: LazyLock<Config> = LazyLock::new(|| Config::default().set_bytes_base64());
+
+        fn base64_bytes<'de, D>(d: D) -> core::result::Result<Vec<u8>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            crate::Deserializer::deserialize_with_config::<serde_bytes::ByteBuf>(d, &BASE64)
+                .map(|bytes| bytes.into_vec())
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Inner {
+            #[serde(deserialize_with = "base64_bytes")]
+            signature: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            digest: Vec<u8>,
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Outer {
+            #[serde(with = "serde_bytes")]
+            hash: Vec<u8>,
+            inner: Inner,
+        }
+
+        let expected = Outer {
+            hash: vec![0xab, 0xcd],
+            inner: Inner {
+                signature: vec![1, 2, 255],
+                digest: vec![0x01, 0x02],
+            },
+        };
+
+        let json = r#"{"hash":"0xabcd","inner":{"signature":"AQL/","digest":"0x0102"}}"#;
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result: Outer = from_str(json, &config).unwrap();
+        assert_eq!(result, expected);
+
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let result: Outer = from_value(value, &config).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_wrapped_deserializer_error_message() {
+        let config = Config::default().set_bytes_hex();
+        let mut inner = serde_json::Deserializer::from_str("\"0x0g\"");
+        let err = serde_bytes::ByteBuf::deserialize(Deserializer::with_config(&mut inner, &config))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid hex string: Invalid character 'g' at position 3 at line 1 column 6"
+        );
+
+        let err = from_str::<serde_bytes::ByteBuf>("\n  \"0x0g\"", &config).unwrap_err();
+        assert!(matches!(err, crate::Error::HexDecode { .. }));
+        assert_eq!((err.line(), err.column()), (2, 8));
+    }
+
+    #[test]
+    fn test_round_trip_base64_variants() {
+        use crate::Base64Variant;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![1, 2, 3, 255],
+        };
+
+        let cases = [
+            (Base64Variant::STANDARD, r#"{"data":"AQID/w=="}"#),
+            (Base64Variant::STANDARD_NO_PAD, r#"{"data":"AQID/w"}"#),
+            (Base64Variant::URL_SAFE, r#"{"data":"AQID_w=="}"#),
+            (Base64Variant::URL_SAFE_NO_PAD, r#"{"data":"AQID_w"}"#),
+        ];
+        for (variant, expected) in cases {
+            let config = Config::default().set_bytes_b64_variant(variant);
+            assert_eq!(config.bytes_format(), BytesFormat::Base64(variant));
+            let json = crate::to_string(&value, &config).unwrap();
+            assert_eq!(json, expected);
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, value);
+
+            let config = config.enable_base64_strict_padding();
+            let result: TestStruct = from_str(&json, &config).unwrap();
+            assert_eq!(result, value);
+        }
+
+        // The split builders map onto variants
+        let config = Config::default().set_bytes_base64_url_safe();
+        assert_eq!(
+            config.bytes_format(),
+            BytesFormat::Base64(Base64Variant::URL_SAFE)
+        );
+        let config = Config::default().set_bytes_base64_standard_no_pad();
+        assert_eq!(
+            config.bytes_format(),
+            BytesFormat::Base64(Base64Variant::STANDARD_NO_PAD)
+        );
+
+        // The deprecated variant still works where a `BytesFormat` is taken
+        #[allow(deprecated)]
+        let config = Config::default()
+            .set_bytes_hex()
+            .set_bytes_decode_order(vec![BytesFormat::Base64UrlSafe]);
+        let result: TestStruct = from_str(r#"{"data":"AQID_w=="}"#, &config).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_from_str_base64_strict_padding() {
+        let config = Config::default().set_bytes_base64_standard_no_pad();
+        assert!(config.base64_strict_padding());
+        assert_eq!(
+            crate::to_string(&serde_bytes::Bytes::new(&[1, 2, 3, 255]), &config).unwrap(),
+            r#""AQID/w""#
+        );
+        assert_eq!(
+            from_str_bytes(r#""AQID/w""#, &config).unwrap(),
+            [1, 2, 3, 255]
+        );
+
+        // Padded input under a no-pad config
+        let err = from_str_bytes(r#""AQID/w==""#, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::Base64Decode { .. }));
+        let config = config.disable_base64_strict_padding();
+        assert!(!config.base64_strict_padding());
+        assert_eq!(
+            from_str_bytes(r#""AQID/w==""#, &config).unwrap(),
+            [1, 2, 3, 255]
+        );
+
+        // Unpadded input under a padded config
+        let config = Config::default().set_bytes_base64();
+        assert_eq!(
+            from_str_bytes(r#""AQID/w==""#, &config).unwrap(),
+            [1, 2, 3, 255]
+        );
+        let err = from_str_bytes(r#""AQID/w""#, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::Base64Decode { .. }));
+
+        let config = Config::default().set_bytes_base64_url_safe();
+        assert!(from_str_bytes(r#""AQID_w""#, &config).is_err());
+        let config = config.disable_base64_strict_padding();
+        assert_eq!(
+            from_str_bytes(r#""AQID_w""#, &config).unwrap(),
+            [1, 2, 3, 255]
+        );
+        let config = config.enable_base64_strict_padding();
+        assert!(from_str_bytes(r#""AQID_w""#, &config).is_err());
+    }
+
+    #[test]
+    fn test_from_str_base64_length_validation() {
+        let config = Config::default()
+            .set_bytes_base64()
+            .disable_base64_strict_padding();
+        let err = from_str_bytes(r#""AQIDB""#, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::Base64Decode { .. }));
+
+        let config = config.enable_base64_length_validation();
+        assert!(config.validate_base64_length());
+        let err = from_str_bytes(r#""AQIDB""#, &config).unwrap_err();
+        assert!(matches!(err, crate::Error::BytesDecode { .. }));
+        assert!(err.to_string().contains("invalid base64 length 5"));
+
+        // Unpadded lengths of 2 and 3 mod 4 still decode
+        assert_eq!(
+            from_str_bytes(r#""AQID/w""#, &config).unwrap(),
+            [1, 2, 3, 255]
+        );
+        assert_eq!(from_str_bytes(r#""AQI""#, &config).unwrap(), [1, 2]);
+        assert!(from_str_bytes(r#""""#, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_bytes_transform() {
+        use alloc::sync::Arc;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(with = "crate::bytes_array")]
+            id: [u8; 2],
+        }
+
+        let value = TestStruct {
+            data: vec![0x00, 0x0f, 0xf0],
+            id: [0x12, 0x34],
+        };
+
+        let identity: crate::BytesTransformFn = Arc::new(|b: &[u8]| b.to_vec());
+        let config = Config::default()
+            .set_bytes_hex()
+            .set_bytes_transform(identity.clone())
+            .set_bytes_untransform(identity);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"000ff0","id":"1234"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let xor: crate::BytesTransformFn =
+            Arc::new(|b: &[u8]| b.iter().map(|b| b ^ 0xff).collect());
+        let config = Config::default()
+            .set_bytes_hex()
+            .set_bytes_transform(xor.clone())
+            .set_bytes_untransform(xor);
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":"fff00f","id":"edcb"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Without the inverse the transformed bytes come back as written
+        let config = config.clear_bytes_untransform();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result.data, [0xff, 0xf0, 0x0f]);
+    }
+
+    #[test]
+    fn test_round_trip_bytes_checksum_suffix() {
+        use crate::Error;
+
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            #[serde(with = "crate::bytes_array")]
+            id: [u8; 2],
+        }
+
+        let value = TestStruct {
+            data: b"123456789".to_vec(),
+            id: [0x12, 0x34],
+        };
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .set_bytes_checksum_suffix();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":"313233343536373839cbf43926","id":"123418999699"}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = config.set_bytes_base64();
+        let json = crate::to_string(&value, &config).unwrap();
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // One flipped payload bit
+        let config = config.set_bytes_hex();
+        let corrupted = r#"{"data":"313233343536373838cbf43926","id":"123418999699"}"#;
+        let err = from_str::<TestStruct>(corrupted, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::ChecksumMismatch {
+                expected: 0xcbf4_3926,
+                ..
+            }
+        ));
+
+        let err = from_str::<TestStruct>(r#"{"data":"cbf439","id":"1234"}"#, &config).unwrap_err();
+        assert!(matches!(err, Error::BytesDecode { .. }));
+    }
+
+    #[test]
+    fn test_from_str_hex_ignore_whitespace() {
+        let json = r#""0x0102 0304\n\t0506 \r\n0708""#;
+
+        let config = Config::default().set_bytes_hex();
+        assert!(from_str_bytes(json, &config).is_err());
+
+        let config = config.enable_hex_ignore_whitespace();
+        assert!(config.hex_ignore_whitespace());
+        let bytes = from_str_bytes(json, &config).unwrap();
+        assert_eq!(bytes, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let json = r#""hex:\n  0102\n  0304\n""#;
+        let config = config.set_bytes_prefix("hex:");
+        let bytes = from_str_bytes(json, &config).unwrap();
+        assert_eq!(bytes, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_round_trip_hex_0x_only_if_nonempty() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            empty: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            empty: vec![],
+            data: vec![0xab],
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        assert!(!config.hex_0x_only_if_nonempty());
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"empty":"0x","data":"0xab"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let config = config.enable_hex_0x_only_if_nonempty();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"empty":"","data":"0xab"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Both forms of empty bytes are accepted either way
+        for json in [
+            r#"{"empty":"","data":"0xab"}"#,
+            r#"{"empty":"0x","data":"0xab"}"#,
+        ] {
+            let result: TestStruct = from_str(json, &config).unwrap();
+            assert_eq!(result, value);
+            let config = config.clone().disable_hex_0x_only_if_nonempty();
+            let result: TestStruct = from_str(json, &config).unwrap();
+            assert_eq!(result, value);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_bytes_auto_by_length() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            short: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            long: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_format_auto_by_length();
+
+        let value = TestStruct {
+            short: vec![0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+            long: b"hello world!".to_vec(),
+        };
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"short":"0x0102030405060708","long":"aGVsbG8gd29ybGQh"}"#
+        );
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // This base64 would read back as hex, so the bytes are written as hex
+        let value = TestStruct {
+            short: vec![],
+            long: vec![0xd3, 0x1d, 0x35, 0xdb, 0x7e, 0x39, 0xeb, 0xbf, 0x3d],
+        };
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"short":"0x","long":"0xd31d35db7e39ebbf3d"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_round_trip_hex_or_integer() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "crate::bytes_array")]
+            id: [u8; 4],
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            id: [0xde, 0xad, 0xbe, 0xef],
+            data: vec![0x01; 9],
+        };
+
+        let config = Config::default()
+            .set_bytes_hex_or_integer()
+            .enable_hex_prefix();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"id":3735928559,"data":"0x010101010101010101"}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        // Numbers decode to their minimal width
+        let bytes = from_str_bytes("256", &config).unwrap();
+        assert_eq!(bytes, [0x01, 0x00]);
+        let bytes = from_str_bytes("0", &config).unwrap();
+        assert_eq!(bytes, [0x00]);
+        let bytes = from_str_bytes("18446744073709551615", &config).unwrap();
+        assert_eq!(bytes, [0xff; 8]);
+        let json = crate::to_string(&serde_bytes::Bytes::new(&[0x00, 0x01]), &config).unwrap();
+        assert_eq!(json, "1");
+        assert_eq!(from_str_bytes(&json, &config).unwrap(), [0x01]);
+
+        let json = crate::to_string(&serde_bytes::Bytes::new(&[]), &config).unwrap();
+        assert_eq!(json, r#""0x""#);
+        assert!(from_str_bytes(&json, &config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_round_trip_hex_and_len() {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            data: vec![0x01, 0xff],
+        };
+
+        let config = Config::default().set_bytes_hex_and_len();
+        let json = crate::to_string(&value, &config).unwrap();
+        assert_eq!(json, r#"{"data":{"len":2,"hex":"0x01ff"}}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, value);
+
+        let empty = TestStruct { data: vec![] };
+        let json = crate::to_string(&empty, &config).unwrap();
+        assert_eq!(json, r#"{"data":{"len":0,"hex":"0x"}}"#);
+        let result: TestStruct = from_str(&json, &config).unwrap();
+        assert_eq!(result, empty);
+
+        // `len` and the 0x prefix may be omitted
+        let result: TestStruct = from_str(r#"{"data":{"hex":"01ff"}}"#, &config).unwrap();
+        assert_eq!(result, value);
+    }
+
+    #[test]
+    fn test_from_str_hex_and_len_mismatch() {
+        #[derive(Deserialize, Debug)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            #[allow(dead_code)]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_hex_and_len();
+        let json = r#"{"data":{"len":3,"hex":"0x01ff"}}"#;
+        let err = from_str::<TestStruct>(json, &config).unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::LengthMismatch {
+                expected: 3,
+                actual: 2,
+                ..
+            }
+        ));
+    }
+
+    #[cfg(feature = "value-bytes")]
+    #[test]
+    fn test_from_str_decoding_bytes() {
+        use serde_json::json;
+
+        let json = r#"{"payload":"0x01ff","name":"alice","nested":[{"id":"0xcafe"}],"n":7}"#;
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let value = from_str_decoding_bytes(json, &config).unwrap();
+        assert_eq!(
+            value,
+            json!({"payload": [1, 255], "name": "alice", "nested": [{"id": [202, 254]}], "n": 7})
+        );
+
+        // Bytes written as arrays leave every string alone
+        let value = from_str_decoding_bytes(json, &Config::default()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(json).unwrap()
+        );
+
+        // Empty and plain text strings are not marked as bytes
+        let json = r#"["", "1234", "cafe", "0x12"]"#;
+        let value = from_str_decoding_bytes(json, &config).unwrap();
+        assert_eq!(value, json!(["", "1234", "cafe", [0x12]]));
+        let config = Config::default().set_bytes_hex();
+        let value = from_str_decoding_bytes(json, &config).unwrap();
+        assert_eq!(value, json!(["", "1234", "cafe", "0x12"]));
+
+        // Every format of the decode order is tried on marked strings
+        let config = Config::default()
+            .set_bytes_prefix("b:")
+            .set_bytes_decode_order(vec![
+                BytesFormat::Hex,
+                BytesFormat::Base64(crate::Base64Variant::STANDARD),
+            ]);
+        let json = r#"["b:0102", "b:AQID/w==", "b:!", "AQID/w=="]"#;
+        let value = from_str_decoding_bytes(json, &config).unwrap();
+        assert_eq!(value, json!([[1, 2], [1, 2, 3, 255], "b:!", "AQID/w=="]));
+    }
 }
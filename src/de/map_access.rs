@@ -1,13 +1,18 @@
 use serde::de::{DeserializeSeed, MapAccess};
 
-use crate::{Config, de::seed::WrapSeed};
+use crate::{
+    Config,
+    de::{context::Context, seed::WrapSeed},
+};
 
 pub struct WrapMapAccess<'a, A> {
     pub inner: A,
     pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
 }
 
-impl<'de, A> MapAccess<'de> for WrapMapAccess<'de, A>
+impl<'de, 'a, A> MapAccess<'de> for WrapMapAccess<'a, A>
 where
     A: MapAccess<'de>,
 {
@@ -20,6 +25,8 @@ where
         self.inner.next_key_seed(WrapSeed {
             seed,
             config: self.config,
+            depth: self.depth,
+            context: self.context,
         })
     }
 
@@ -30,6 +37,8 @@ where
         self.inner.next_value_seed(WrapSeed {
             seed,
             config: self.config,
+            depth: self.depth,
+            context: self.context,
         })
     }
 
@@ -1,10 +1,13 @@
 mod bytes;
+mod context;
 mod deserializer;
 mod enum_access;
 pub mod from;
 mod map_access;
+mod number;
 mod seed;
 mod seq_access;
+mod variant_access;
 // pub mod value;
 mod visitor;
 
@@ -0,0 +1,195 @@
+// Number deserialization utilities
+
+use alloc::{format, string::String};
+use serde::de::{Unexpected, Visitor};
+
+use crate::Config;
+
+/// Deserializes an integer from either a JSON number or a string
+///
+/// Strings are accepted as hexadecimal "0x1a" (optionally negative, "-0x1a")
+/// when `config.accept_hex_numbers` is set, and as decimal with an optional
+/// explicit sign ("+5", "-5") when `config.force_sign` is set. Decimal
+/// strings without a `+` ("5", "-5") are accepted when
+/// `config.numbers_as_strings` is set.
+pub(crate) fn de_number<'de, D, V>(
+    deserializer: D,
+    config: &Config,
+    visitor: V,
+) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct NumberVisitor<'a, V> {
+        config: &'a Config,
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for NumberVisitor<'_, V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            let decimal = self.config.force_sign || self.config.numbers_as_strings;
+            match (self.config.accept_hex_numbers, decimal) {
+                (true, true) => formatter
+                    .write_str("an integer, a signed integer string or a hexadecimal string"),
+                (true, false) => formatter.write_str("an integer or a hexadecimal string"),
+                _ => formatter.write_str("an integer or a signed integer string"),
+            }
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_i64(v)
+        }
+
+        fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_i128(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_u64(v)
+        }
+
+        fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_u128(v)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_f64(v)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            let (negative, rest) = match v.as_bytes().first() {
+                Some(b'-') => (true, &v[1..]),
+                Some(b'+') if self.config.force_sign => (false, &v[1..]),
+                _ => (false, v),
+            };
+            let hex_digits = if self.config.accept_hex_numbers {
+                rest.strip_prefix("0x").or_else(|| rest.strip_prefix("0X"))
+            } else {
+                None
+            };
+            let (digits, radix) = match hex_digits {
+                Some(digits) => (digits, 16),
+                None if self.config.force_sign || self.config.numbers_as_strings => (rest, 10),
+                None => return Err(E::invalid_value(Unexpected::Str(v), &self)),
+            };
+            if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+                return Err(E::invalid_value(Unexpected::Str(v), &self));
+            }
+            let n = u128::from_str_radix(digits, radix)
+                .map_err(|e| E::custom(format!("invalid number: {}", e)))?;
+
+            if negative {
+                let n = 0i128
+                    .checked_sub_unsigned(n)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))?;
+                match i64::try_from(n) {
+                    Ok(n) => self.visitor.visit_i64(n),
+                    Err(_) => self.visitor.visit_i128(n),
+                }
+            } else {
+                match u64::try_from(n) {
+                    Ok(n) => self.visitor.visit_u64(n),
+                    Err(_) => self.visitor.visit_u128(n),
+                }
+            }
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_any(NumberVisitor { config, visitor })
+}
+
+/// Deserializes a float from either a JSON number or a decimal string such as "1.5"
+///
+/// Used when `config.numbers_as_strings` is set.
+pub(crate) fn de_float<'de, D, V>(deserializer: D, visitor: V) -> Result<V::Value, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+    V: Visitor<'de>,
+{
+    struct FloatVisitor<V> {
+        visitor: V,
+    }
+
+    impl<'de, V> Visitor<'de> for FloatVisitor<V>
+    where
+        V: Visitor<'de>,
+    {
+        type Value = V::Value;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a number or a numeric string")
+        }
+
+        fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_i64(v)
+        }
+
+        fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_u64(v)
+        }
+
+        fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visitor.visit_f64(v)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            // Rust also parses "inf" and "NaN", which JSON numbers cannot hold
+            match v.parse::<f64>() {
+                Ok(n) if n.is_finite() => self.visitor.visit_f64(n),
+                _ => Err(E::invalid_value(Unexpected::Str(v), &self)),
+            }
+        }
+
+        fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            self.visit_str(&v)
+        }
+    }
+
+    deserializer.deserialize_any(FloatVisitor { visitor })
+}
@@ -1,13 +1,18 @@
 use serde::de;
 
-use crate::{Config, de::Deserializer};
+use crate::{
+    Config,
+    de::{Deserializer, context::Context},
+};
 
 pub struct WrapSeed<'a, S> {
     pub seed: S,
     pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
 }
 
-impl<'de, S> de::DeserializeSeed<'de> for WrapSeed<'de, S>
+impl<'de, 'a, S> de::DeserializeSeed<'de> for WrapSeed<'a, S>
 where
     S: de::DeserializeSeed<'de>,
 {
@@ -17,7 +22,7 @@ where
     where
         D2: de::Deserializer<'de>,
     {
-        let de = Deserializer::with_config(de2, self.config);
+        let de = Deserializer::with_state(de2, self.config, self.depth, self.context);
 
         self.seed.deserialize(de)
     }
@@ -1,13 +1,18 @@
 use serde::de::{DeserializeSeed, SeqAccess};
 
-use crate::{Config, de::seed::WrapSeed};
+use crate::{
+    Config,
+    de::{context::Context, seed::WrapSeed},
+};
 
 pub struct WrapSeqAccess<'a, A> {
     pub inner: A,
     pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
 }
 
-impl<'de, A> SeqAccess<'de> for WrapSeqAccess<'de, A>
+impl<'de, 'a, A> SeqAccess<'de> for WrapSeqAccess<'a, A>
 where
     A: SeqAccess<'de>,
 {
@@ -20,6 +25,8 @@ where
         self.inner.next_element_seed(WrapSeed {
             seed,
             config: self.config,
+            depth: self.depth,
+            context: self.context,
         })
     }
 
@@ -0,0 +1,70 @@
+use serde::de::{DeserializeSeed, VariantAccess, Visitor};
+
+use crate::{
+    Config,
+    de::{WrapVisitor, context::Context, seed::WrapSeed},
+};
+
+pub struct WrapVariantAccess<'a, A> {
+    pub inner: A,
+    pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
+}
+
+impl<'de, 'a, A> VariantAccess<'de> for WrapVariantAccess<'a, A>
+where
+    A: VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.inner.unit_variant()
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.inner.newtype_variant_seed(WrapSeed {
+            seed,
+            config: self.config,
+            depth: self.depth,
+            context: self.context,
+        })
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.tuple_variant(
+            len,
+            WrapVisitor {
+                visitor,
+                config: self.config,
+                depth: self.depth,
+                context: self.context,
+            },
+        )
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.inner.struct_variant(
+            fields,
+            WrapVisitor {
+                visitor,
+                config: self.config,
+                depth: self.depth,
+                context: self.context,
+            },
+        )
+    }
+}
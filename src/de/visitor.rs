@@ -1,17 +1,56 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
 use serde::de::{EnumAccess, MapAccess, SeqAccess, Visitor};
-use std::fmt;
 
 use crate::{
     Config,
-    de::{enum_access::WrapEnumAccess, map_access::WrapMapAccess, seq_access::WrapSeqAccess},
+    de::{
+        Deserializer, context::Context, enum_access::WrapEnumAccess, map_access::WrapMapAccess,
+        seq_access::WrapSeqAccess,
+    },
 };
 
+/// Fails if `v` is longer than the configured `max_string_len`
+pub(crate) fn check_string_len<E>(config: &Config, v: &str) -> Result<(), E>
+where
+    E: serde::de::Error,
+{
+    match config.max_string_len {
+        Some(max_len) if v.len() > max_len => Err(E::custom(format_args!(
+            "string of {} bytes exceeds the maximum length of {}",
+            v.len(),
+            max_len
+        ))),
+        _ => Ok(()),
+    }
+}
+
 pub struct WrapVisitor<'a, V> {
     pub visitor: V,
     pub config: &'a Config,
+    pub depth: usize,
+    pub context: Option<&'a Context>,
+}
+
+impl<V> WrapVisitor<'_, V> {
+    /// Returns the depth of the container being entered, failing once it
+    /// would exceed the configured `max_depth`
+    fn enter<E>(&self) -> Result<usize, E>
+    where
+        E: serde::de::Error,
+    {
+        let depth = self.depth + 1;
+        match self.config.max_depth {
+            Some(max_depth) if depth > max_depth => Err(E::custom(format_args!(
+                "maximum nesting depth of {} exceeded",
+                max_depth
+            ))),
+            _ => Ok(depth),
+        }
+    }
 }
 
-impl<'de, V> Visitor<'de> for WrapVisitor<'de, V>
+impl<'de, 'a, V> Visitor<'de> for WrapVisitor<'a, V>
 where
     V: Visitor<'de>,
 {
@@ -123,6 +162,7 @@ where
     where
         E: serde::de::Error,
     {
+        check_string_len(self.config, v)?;
         self.visitor.visit_str(v)
     }
 
@@ -130,6 +170,7 @@ where
     where
         E: serde::de::Error,
     {
+        check_string_len(self.config, v)?;
         self.visitor.visit_borrowed_str(v)
     }
 
@@ -137,6 +178,7 @@ where
     where
         E: serde::de::Error,
     {
+        check_string_len(self.config, &v)?;
         self.visitor.visit_string(v)
     }
 
@@ -172,7 +214,12 @@ where
     where
         D: serde::de::Deserializer<'de>,
     {
-        self.visitor.visit_some(deserializer)
+        self.visitor.visit_some(Deserializer::with_state(
+            deserializer,
+            self.config,
+            self.depth,
+            self.context,
+        ))
     }
 
     fn visit_unit<E>(self) -> Result<Self::Value, E>
@@ -186,16 +233,24 @@ where
     where
         D: serde::de::Deserializer<'de>,
     {
-        self.visitor.visit_newtype_struct(deserializer)
+        self.visitor.visit_newtype_struct(Deserializer::with_state(
+            deserializer,
+            self.config,
+            self.depth,
+            self.context,
+        ))
     }
 
     fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
     where
         A: SeqAccess<'de>,
     {
+        let depth = self.enter::<A::Error>()?;
         self.visitor.visit_seq(WrapSeqAccess {
             inner: seq,
             config: self.config,
+            depth,
+            context: self.context,
         })
     }
 
@@ -203,9 +258,12 @@ where
     where
         A: MapAccess<'de>,
     {
+        let depth = self.enter::<A::Error>()?;
         self.visitor.visit_map(WrapMapAccess {
             inner: map,
             config: self.config,
+            depth,
+            context: self.context,
         })
     }
 
@@ -213,9 +271,12 @@ where
     where
         A: EnumAccess<'de>,
     {
+        let depth = self.enter::<A::Error>()?;
         self.visitor.visit_enum(WrapEnumAccess {
             inner: data,
             config: self.config,
+            depth,
+            context: self.context,
         })
     }
 }
@@ -0,0 +1,250 @@
+// Error type for serialization and deserialization
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::BytesFormat;
+
+/// Errors returned by the serialization and deserialization functions
+///
+/// Byte decoding errors carry the position serde_json reported them at, as
+/// `line` and `column` starting at 1. Both are 0 when the input has no
+/// position, as with `from_value`.
+#[derive(Debug)]
+pub enum Error {
+    /// An error from serde_json, such as malformed JSON or a type mismatch
+    Json(serde_json::Error),
+    /// A hex byte string could not be decoded
+    HexDecode {
+        /// The failure reported by the hex decoder
+        error: hex::FromHexError,
+        /// Line of the input the error was reported at
+        line: usize,
+        /// Column of the input the error was reported at
+        column: usize,
+    },
+    /// A base64 byte string could not be decoded
+    Base64Decode {
+        /// The failure reported by the base64 decoder
+        error: base64::DecodeError,
+        /// Line of the input the error was reported at
+        line: usize,
+        /// Column of the input the error was reported at
+        column: usize,
+    },
+    /// A byte string in another format could not be decoded
+    BytesDecode {
+        /// The bytes format that was active
+        format: BytesFormat,
+        /// Description of the failure
+        message: String,
+        /// Line of the input the error was reported at
+        line: usize,
+        /// Column of the input the error was reported at
+        column: usize,
+    },
+    /// Decoded bytes did not have the expected length
+    LengthMismatch {
+        /// The required number of bytes
+        expected: usize,
+        /// The number of bytes decoded
+        actual: usize,
+        /// Line of the input the error was reported at
+        line: usize,
+        /// Column of the input the error was reported at
+        column: usize,
+    },
+    /// The CRC-32 suffix of decoded bytes did not match their contents
+    ChecksumMismatch {
+        /// The CRC-32 read from the suffix
+        expected: u32,
+        /// The CRC-32 computed over the bytes before the suffix
+        actual: u32,
+        /// Line of the input the error was reported at
+        line: usize,
+        /// Column of the input the error was reported at
+        column: usize,
+    },
+    /// A reader produced more input than `Config::set_max_input_bytes` allows
+    InputTooLarge {
+        /// The configured limit in bytes
+        limit: usize,
+    },
+}
+
+/// Alias for a `Result` with the error type `serde_json_ext::Error`
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl Error {
+    /// Line of the input the error was reported at, starting at 1, or 0 if
+    /// it has no position
+    pub fn line(&self) -> usize {
+        match self {
+            Error::Json(e) => e.line(),
+            Error::HexDecode { line, .. }
+            | Error::Base64Decode { line, .. }
+            | Error::BytesDecode { line, .. }
+            | Error::LengthMismatch { line, .. }
+            | Error::ChecksumMismatch { line, .. } => *line,
+            Error::InputTooLarge { .. } => 0,
+        }
+    }
+
+    /// Column of the input the error was reported at, starting at 1, or 0 if
+    /// it has no position
+    pub fn column(&self) -> usize {
+        match self {
+            Error::Json(e) => e.column(),
+            Error::HexDecode { column, .. }
+            | Error::Base64Decode { column, .. }
+            | Error::BytesDecode { column, .. }
+            | Error::LengthMismatch { column, .. }
+            | Error::ChecksumMismatch { column, .. } => *column,
+            Error::InputTooLarge { .. } => 0,
+        }
+    }
+
+    pub(crate) fn hex(error: hex::FromHexError) -> Self {
+        Error::HexDecode {
+            error,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn base64(error: base64::DecodeError) -> Self {
+        Error::Base64Decode {
+            error,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn bytes(format: BytesFormat, message: impl Into<String>) -> Self {
+        Error::BytesDecode {
+            format,
+            message: message.into(),
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn length(expected: usize, actual: usize) -> Self {
+        Error::LengthMismatch {
+            expected,
+            actual,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    pub(crate) fn checksum(expected: u32, actual: u32) -> Self {
+        Error::ChecksumMismatch {
+            expected,
+            actual,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    /// Moves a byte decoding error to the position of `error`, the serde_json
+    /// error it was returned as
+    pub(crate) fn at(mut self, error: &serde_json::Error) -> Self {
+        match &mut self {
+            Error::HexDecode { line, column, .. }
+            | Error::Base64Decode { line, column, .. }
+            | Error::BytesDecode { line, column, .. }
+            | Error::LengthMismatch { line, column, .. }
+            | Error::ChecksumMismatch { line, column, .. } => {
+                *line = error.line();
+                *column = error.column();
+            }
+            Error::Json(_) | Error::InputTooLarge { .. } => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Json(e) => return write!(f, "{}", e),
+            Error::HexDecode { error, .. } => write!(f, "invalid hex string: {}", error)?,
+            Error::Base64Decode { error, .. } => write!(f, "invalid base64 string: {}", error)?,
+            Error::BytesDecode { message, .. } => write!(f, "{}", message)?,
+            Error::LengthMismatch {
+                expected, actual, ..
+            } => write!(f, "invalid length {}, expected {} bytes", actual, expected)?,
+            Error::ChecksumMismatch {
+                expected, actual, ..
+            } => write!(
+                f,
+                "crc32 mismatch: suffix is {:08x}, computed {:08x}",
+                expected, actual
+            )?,
+            Error::InputTooLarge { limit } => {
+                write!(f, "input exceeds the limit of {} bytes", limit)?
+            }
+        }
+        // As serde_json writes the position of its own errors
+        match self.line() {
+            0 => Ok(()),
+            line => write!(f, " at line {} column {}", line, self.column()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Json(e) => Some(e),
+            Error::HexDecode { error, .. } => Some(error),
+            Error::Base64Decode { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Error::Json(error)
+    }
+}
+
+impl From<Error> for serde_json::Error {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Json(e) => e,
+            e => <serde_json::Error as serde::de::Error>::custom(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_error_position() {
+        let json: serde_json::Error = serde_json::from_str::<u8>("\n  x").unwrap_err();
+        let error = Error::hex(hex::FromHexError::OddLength);
+        assert_eq!((error.line(), error.column()), (0, 0));
+        assert_eq!(
+            error.to_string(),
+            "invalid hex string: Odd number of digits"
+        );
+
+        let error = error.at(&json);
+        assert_eq!((error.line(), error.column()), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "invalid hex string: Odd number of digits at line 2 column 3"
+        );
+
+        let error = Error::from(json);
+        assert!(matches!(error, Error::Json(_)));
+        assert_eq!((error.line(), error.column()), (2, 3));
+    }
+}
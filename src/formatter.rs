@@ -0,0 +1,624 @@
+// Formatters honoring the layout options of `Config`
+
+use serde_json::ser::{CharEscape, CompactFormatter, Formatter};
+use std::{borrow::Cow, io};
+
+use crate::Config;
+
+/// Kind of a buffered container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Array,
+    Object,
+}
+
+/// A single array element or object entry of a buffered container
+#[derive(Debug, Default)]
+struct Item {
+    /// Rendered key (objects only)
+    key: Vec<u8>,
+    /// Rendered value
+    value: Vec<u8>,
+    /// Whether the value is itself an array or object
+    nested: bool,
+}
+
+/// A container whose layout is decided once all of its items are known
+#[derive(Debug)]
+struct Frame {
+    container: Container,
+    items: Vec<Item>,
+    /// Whether output currently goes to the key of the last item
+    in_key: bool,
+}
+
+/// Escapes U+2028 and U+2029 in a string fragment if the config asks for it
+///
+/// Both are valid in JSON strings but end a line in JavaScript, which breaks
+/// JSON embedded in a script such as a JSONP response.
+fn escape_fragment<'s>(config: &Config, fragment: &'s str) -> Cow<'s, str> {
+    if config.escape_js_line_separators && fragment.contains(['\u{2028}', '\u{2029}']) {
+        Cow::Owned(
+            fragment
+                .replace('\u{2028}', "\\u2028")
+                .replace('\u{2029}', "\\u2029"),
+        )
+    } else {
+        Cow::Borrowed(fragment)
+    }
+}
+
+/// A compact formatter honoring the string escaping options of `Config`
+///
+/// Apart from escaping it produces the same output as
+/// `serde_json::ser::CompactFormatter`.
+pub(crate) struct EscapingCompactFormatter<'a> {
+    config: &'a Config,
+}
+
+impl<'a> EscapingCompactFormatter<'a> {
+    /// Creates a new `EscapingCompactFormatter` with custom config
+    pub(crate) fn new(config: &'a Config) -> Self {
+        EscapingCompactFormatter { config }
+    }
+}
+
+impl Formatter for EscapingCompactFormatter<'_> {
+    fn write_string_fragment<W>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        writer.write_all(escape_fragment(self.config, fragment).as_bytes())
+    }
+}
+
+/// A pretty formatter with configurable layout
+///
+/// Without any layout option enabled it produces the same output as
+/// `serde_json::ser::PrettyFormatter`. Layout options that depend on the
+/// contents of a container (such as keeping short arrays inline) buffer
+/// each container until it ends.
+pub(crate) struct PrettyFormatter<'a> {
+    config: &'a Config,
+    /// Write every container on one line with no spaces, as compact output
+    compact: bool,
+    current_indent: usize,
+    has_value: bool,
+    indent: &'a [u8],
+    frames: Vec<Frame>,
+}
+
+impl<'a> PrettyFormatter<'a> {
+    /// Creates a new `PrettyFormatter` with custom config
+    pub(crate) fn new(config: &'a Config) -> Self {
+        PrettyFormatter {
+            config,
+            compact: false,
+            current_indent: 0,
+            has_value: false,
+            indent: &config.indent,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Creates a formatter for compact output with the options that need
+    /// buffering, such as JSON5 keys
+    pub(crate) fn compact(config: &'a Config) -> Self {
+        PrettyFormatter {
+            compact: true,
+            ..PrettyFormatter::new(config)
+        }
+    }
+
+    /// Returns true if containers must be buffered to decide their layout
+    fn buffering(&self) -> bool {
+        self.compact
+            || self.config.compact_arrays_inline.is_some()
+            || self.config.align_object_values
+            || self.config.pretty_compact_threshold.is_some()
+            || self.config.json5_output
+    }
+
+    /// Returns the buffer of the item being written, if any
+    fn buffer(&mut self) -> Option<&mut Vec<u8>> {
+        let frame = self.frames.last_mut()?;
+        let in_key = frame.in_key;
+        let item = frame.items.last_mut()?;
+
+        if in_key {
+            Some(&mut item.key)
+        } else {
+            Some(&mut item.value)
+        }
+    }
+
+    fn write_all<W>(&mut self, writer: &mut W, bytes: &[u8]) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => {
+                buf.extend_from_slice(bytes);
+                Ok(())
+            }
+            None => writer.write_all(bytes),
+        }
+    }
+
+    fn begin_frame(&mut self, container: Container) {
+        if let Some(item) = self.frames.last_mut().and_then(|f| f.items.last_mut()) {
+            item.nested = true;
+        }
+
+        self.frames.push(Frame {
+            container,
+            items: Vec::new(),
+            in_key: false,
+        });
+    }
+
+    fn begin_item(&mut self, in_key: bool) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.items.push(Item::default());
+            frame.in_key = in_key;
+        }
+    }
+
+    fn end_frame<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.frames.pop() {
+            Some(frame) => {
+                let rendered = self.render(&frame);
+                self.write_all(writer, &rendered)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns true if the container fits on a single line
+    fn is_inline(&self, frame: &Frame) -> bool {
+        match self.config.compact_arrays_inline {
+            Some(max_len) => {
+                frame.container == Container::Array
+                    && frame.items.len() <= max_len
+                    && frame.items.iter().all(|item| !item.nested)
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the length of the container written compactly, unless an item spans several lines
+    fn compact_len(frame: &Frame) -> Option<usize> {
+        let mut len = 2 + frame.items.len().saturating_sub(1);
+        for item in &frame.items {
+            if item.value.contains(&b'\n') {
+                return None;
+            }
+            len += item.value.len();
+            if frame.container == Container::Object {
+                len += item.key.len() + 1;
+            }
+        }
+        Some(len)
+    }
+
+    fn render(&self, frame: &Frame) -> Vec<u8> {
+        let depth = self.frames.len();
+        let (open, close) = match frame.container {
+            Container::Array => (b'[', b']'),
+            Container::Object => (b'{', b'}'),
+        };
+
+        let mut out = vec![open];
+
+        if frame.items.is_empty() {
+            out.push(close);
+            return out;
+        }
+
+        let fits = |max_len| Self::compact_len(frame).is_some_and(|len| len <= max_len);
+        if self.compact || self.config.pretty_compact_threshold.is_some_and(fits) {
+            for (i, item) in frame.items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                if frame.container == Container::Object {
+                    out.extend_from_slice(&item.key);
+                    out.push(b':');
+                }
+                out.extend_from_slice(&item.value);
+            }
+            out.push(close);
+            return out;
+        }
+
+        let inline = self.is_inline(frame);
+        let key_width = |item: &Item| String::from_utf8_lossy(&item.key).chars().count();
+        let align = if self.config.align_object_values && frame.container == Container::Object {
+            frame.items.iter().map(key_width).max().unwrap_or(0)
+        } else {
+            0
+        };
+
+        for (i, item) in frame.items.iter().enumerate() {
+            if inline {
+                if i > 0 {
+                    out.extend_from_slice(b", ");
+                }
+            } else {
+                out.extend_from_slice(if i == 0 { b"\n" } else { b",\n" });
+                push_indent(&mut out, depth + 1, self.indent);
+            }
+
+            if frame.container == Container::Object {
+                out.extend_from_slice(&item.key);
+                out.extend_from_slice(b": ");
+                let padding = align.saturating_sub(key_width(item));
+                out.resize(out.len() + padding, b' ');
+            }
+            out.extend_from_slice(&item.value);
+        }
+
+        if !inline {
+            if self.config.json5_output && self.config.json5_trailing_commas {
+                out.push(b',');
+            }
+            out.push(b'\n');
+            push_indent(&mut out, depth, self.indent);
+        }
+
+        out.push(close);
+        out
+    }
+}
+
+/// Returns the key without quotes if it is an ASCII identifier such as `"max_len"`
+///
+/// Keys are rendered with their quotes; anything with an escape or a
+/// character outside `[A-Za-z0-9_$]` keeps them.
+fn identifier_key(key: &[u8]) -> Option<&[u8]> {
+    let name = key.strip_prefix(b"\"")?.strip_suffix(b"\"")?;
+    let (first, rest) = name.split_first()?;
+    let is_start = |b: &u8| b.is_ascii_alphabetic() || *b == b'_' || *b == b'$';
+    let is_part = |b: &u8| is_start(b) || b.is_ascii_digit();
+    (is_start(first) && rest.iter().all(is_part)).then_some(name)
+}
+
+fn push_indent(out: &mut Vec<u8>, n: usize, indent: &[u8]) {
+    for _ in 0..n {
+        out.extend_from_slice(indent);
+    }
+}
+
+fn indent<W>(writer: &mut W, n: usize, indent: &[u8]) -> io::Result<()>
+where
+    W: ?Sized + io::Write,
+{
+    for _ in 0..n {
+        writer.write_all(indent)?;
+    }
+    Ok(())
+}
+
+impl<'a> Formatter for PrettyFormatter<'a> {
+    fn write_null<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_null(buf),
+            None => CompactFormatter.write_null(writer),
+        }
+    }
+
+    fn write_bool<W>(&mut self, writer: &mut W, value: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_bool(buf, value),
+            None => CompactFormatter.write_bool(writer, value),
+        }
+    }
+
+    fn write_i8<W>(&mut self, writer: &mut W, value: i8) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_i8(buf, value),
+            None => CompactFormatter.write_i8(writer, value),
+        }
+    }
+
+    fn write_i16<W>(&mut self, writer: &mut W, value: i16) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_i16(buf, value),
+            None => CompactFormatter.write_i16(writer, value),
+        }
+    }
+
+    fn write_i32<W>(&mut self, writer: &mut W, value: i32) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_i32(buf, value),
+            None => CompactFormatter.write_i32(writer, value),
+        }
+    }
+
+    fn write_i64<W>(&mut self, writer: &mut W, value: i64) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_i64(buf, value),
+            None => CompactFormatter.write_i64(writer, value),
+        }
+    }
+
+    fn write_i128<W>(&mut self, writer: &mut W, value: i128) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_i128(buf, value),
+            None => CompactFormatter.write_i128(writer, value),
+        }
+    }
+
+    fn write_u8<W>(&mut self, writer: &mut W, value: u8) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_u8(buf, value),
+            None => CompactFormatter.write_u8(writer, value),
+        }
+    }
+
+    fn write_u16<W>(&mut self, writer: &mut W, value: u16) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_u16(buf, value),
+            None => CompactFormatter.write_u16(writer, value),
+        }
+    }
+
+    fn write_u32<W>(&mut self, writer: &mut W, value: u32) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_u32(buf, value),
+            None => CompactFormatter.write_u32(writer, value),
+        }
+    }
+
+    fn write_u64<W>(&mut self, writer: &mut W, value: u64) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_u64(buf, value),
+            None => CompactFormatter.write_u64(writer, value),
+        }
+    }
+
+    fn write_u128<W>(&mut self, writer: &mut W, value: u128) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_u128(buf, value),
+            None => CompactFormatter.write_u128(writer, value),
+        }
+    }
+
+    fn write_f32<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_f32(buf, value),
+            None => CompactFormatter.write_f32(writer, value),
+        }
+    }
+
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_f64(buf, value),
+            None => CompactFormatter.write_f64(writer, value),
+        }
+    }
+
+    fn write_number_str<W>(&mut self, writer: &mut W, value: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.write_all(writer, value.as_bytes())
+    }
+
+    fn begin_string<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.write_all(writer, b"\"")
+    }
+
+    fn end_string<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.write_all(writer, b"\"")
+    }
+
+    fn write_string_fragment<W>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        let fragment = escape_fragment(self.config, fragment);
+        self.write_all(writer, fragment.as_bytes())
+    }
+
+    fn write_char_escape<W>(&mut self, writer: &mut W, char_escape: CharEscape) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match self.buffer() {
+            Some(buf) => CompactFormatter.write_char_escape(buf, char_escape),
+            None => CompactFormatter.write_char_escape(writer, char_escape),
+        }
+    }
+
+    fn write_raw_fragment<W>(&mut self, writer: &mut W, fragment: &str) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.write_all(writer, fragment.as_bytes())
+    }
+
+    fn begin_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            self.begin_frame(Container::Array);
+            return Ok(());
+        }
+
+        self.current_indent += 1;
+        self.has_value = false;
+        writer.write_all(b"[")
+    }
+
+    fn end_array<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            return self.end_frame(writer);
+        }
+
+        self.current_indent -= 1;
+
+        if self.has_value {
+            writer.write_all(b"\n")?;
+            indent(writer, self.current_indent, self.indent)?;
+        }
+
+        writer.write_all(b"]")
+    }
+
+    fn begin_array_value<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            self.begin_item(false);
+            return Ok(());
+        }
+
+        writer.write_all(if first { b"\n" } else { b",\n" })?;
+        indent(writer, self.current_indent, self.indent)
+    }
+
+    fn end_array_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.has_value = true;
+        Ok(())
+    }
+
+    fn begin_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            self.begin_frame(Container::Object);
+            return Ok(());
+        }
+
+        self.current_indent += 1;
+        self.has_value = false;
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            return self.end_frame(writer);
+        }
+
+        self.current_indent -= 1;
+
+        if self.has_value {
+            writer.write_all(b"\n")?;
+            indent(writer, self.current_indent, self.indent)?;
+        }
+
+        writer.write_all(b"}")
+    }
+
+    fn begin_object_key<W>(&mut self, writer: &mut W, first: bool) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            self.begin_item(true);
+            return Ok(());
+        }
+
+        writer.write_all(if first { b"\n" } else { b",\n" })?;
+        indent(writer, self.current_indent, self.indent)
+    }
+
+    fn end_object_key<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.in_key = false;
+            if self.config.json5_output
+                && let Some(item) = frame.items.last_mut()
+                && let Some(identifier) = identifier_key(&item.key)
+            {
+                item.key = identifier.to_vec();
+            }
+        }
+        Ok(())
+    }
+
+    fn begin_object_value<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        if self.buffering() {
+            return Ok(());
+        }
+
+        writer.write_all(b": ")
+    }
+
+    fn end_object_value<W>(&mut self, _writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        self.has_value = true;
+        Ok(())
+    }
+}
@@ -1,12 +1,40 @@
 // Library crate for serde_json_helper
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "no-std-ser")))]
+compile_error!("either the `std` or the `no-std-ser` feature must be enabled");
+
+extern crate alloc;
+
 mod config;
+mod config_serde;
 pub use config::*;
 
-// pub(crate) mod formatter;
+mod error;
+pub use error::*;
+
+mod byte_codec;
+pub use byte_codec::*;
+
+#[cfg(feature = "arrayvec")]
+pub mod array_vec_bytes;
+pub mod bytes_array;
+pub(crate) mod codec;
+#[cfg(feature = "std")]
+pub(crate) mod formatter;
+pub mod map_entries;
+pub mod result;
+#[cfg(feature = "smallvec")]
+pub mod small_vec_bytes;
+#[cfg(feature = "std")]
+pub mod time;
+pub mod vec_bytes;
 
 pub(crate) mod ser;
 pub use ser::to::*;
+pub use ser::value::WrapValue;
 
 pub(crate) mod de;
+pub use de::Deserializer;
 pub use de::from::*;
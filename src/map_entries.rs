@@ -0,0 +1,35 @@
+// Serde helpers for maps written as arrays of key-value pairs
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+/// Serializes a map as an array of `[key, value]` pairs
+///
+/// JSON object keys must be strings, so maps keyed by integers, arrays or
+/// bytes either fail or lose their key type. Written as pairs, keys keep
+/// their JSON type and both keys and values follow the configured bytes
+/// format. Use with `#[serde(with = "serde_json_ext::map_entries")]`.
+pub fn serialize<S, M, K, V>(map: &M, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    for<'a> &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+    K: Serialize,
+    V: Serialize,
+{
+    serializer.collect_seq(map)
+}
+
+/// Deserializes a map from an array of `[key, value]` pairs
+///
+/// Later pairs replace earlier ones with an equal key, as collecting into the
+/// map does.
+pub fn deserialize<'de, D, M, K, V>(deserializer: D) -> Result<M, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    M: FromIterator<(K, V)>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    Vec::<(K, V)>::deserialize(deserializer).map(|entries| entries.into_iter().collect())
+}
@@ -0,0 +1,99 @@
+// Serde helpers for `Result` fields
+
+use core::{fmt, marker::PhantomData};
+
+use serde::{
+    Deserialize, Serialize,
+    de::{EnumAccess, VariantAccess, Visitor},
+};
+use serde_json::Value;
+
+/// Enum name that lets the wrappers recognize a `Result` written by this module
+pub(crate) const RESULT_NAME: &str = "$serde_json_ext::Result";
+
+const VARIANTS: &[&str] = &["Ok", "Err"];
+
+/// Serializes a `Result` in the representation set by `Config::set_result_repr`
+///
+/// `ResultRepr::Tagged` writes `{"Ok":...}` or `{"Err":...}`, as serde does,
+/// and `ResultRepr::OkError` writes `{"ok":...,"error":null}` or
+/// `{"ok":null,"error":...}`. Either way the value inside follows the
+/// configured bytes format. Use with
+/// `#[serde(with = "serde_json_ext::result")]`.
+pub fn serialize<S, T, E>(value: &Result<T, E>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: Serialize,
+    E: Serialize,
+{
+    match value {
+        Ok(v) => serializer.serialize_newtype_variant(RESULT_NAME, 0, "Ok", v),
+        Err(e) => serializer.serialize_newtype_variant(RESULT_NAME, 1, "Err", e),
+    }
+}
+
+/// Deserializes a `Result` from the representation set by `Config::set_result_repr`
+///
+/// With `ResultRepr::OkError`, a non-null `error` field yields `Err` and
+/// anything else yields `Ok` with the `ok` field, so `Err(())` cannot be
+/// read back. Missing fields count as `null`.
+pub fn deserialize<'de, D, T, E>(deserializer: D) -> Result<Result<T, E>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    deserializer.deserialize_enum(
+        RESULT_NAME,
+        VARIANTS,
+        ResultVisitor {
+            marker: PhantomData,
+        },
+    )
+}
+
+/// Rewrites an `{"ok":...,"error":...}` object into serde's `{"Ok":...}` or `{"Err":...}`
+pub(crate) fn ok_error_to_tagged(value: Value) -> Result<Value, &'static str> {
+    let Value::Object(mut fields) = value else {
+        return Err("expected an object with `ok` and `error` fields");
+    };
+    let mut tagged = serde_json::Map::new();
+    match fields.remove("error") {
+        Some(error) if !error.is_null() => tagged.insert("Err".into(), error),
+        _ => tagged.insert("Ok".into(), fields.remove("ok").unwrap_or(Value::Null)),
+    };
+    Ok(Value::Object(tagged))
+}
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+enum Variant {
+    Ok,
+    Err,
+}
+
+struct ResultVisitor<T, E> {
+    marker: PhantomData<Result<T, E>>,
+}
+
+impl<'de, T, E> Visitor<'de> for ResultVisitor<T, E>
+where
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    type Value = Result<T, E>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a result")
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+    where
+        A: EnumAccess<'de>,
+    {
+        match data.variant()? {
+            (Variant::Ok, v) => v.newtype_variant().map(Ok),
+            (Variant::Err, v) => v.newtype_variant().map(Err),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+// Canonical form of a serialized value
+
+use alloc::vec::Vec;
+use serde::ser::{SerializeMap as _, SerializeSeq as _};
+use serde_json::{Number, Value};
+
+/// A `serde_json::Value` that serializes in canonical form
+///
+/// Object keys are sorted by their UTF-16 code units, as in RFC 8785 (JCS),
+/// and floats with an integral value of magnitude below 2^53 are written as
+/// integers. Other numbers keep serde_json's shortest round-trip form.
+pub(crate) struct Canonical<'a>(pub(crate) &'a Value);
+
+impl serde::Serialize for Canonical<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.0 {
+            Value::Number(n) => canonical_number(n).serialize(serializer),
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&Canonical(item))?;
+                }
+                seq.end()
+            }
+            Value::Object(object) => {
+                let mut entries: Vec<_> = object.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in entries {
+                    map.serialize_entry(key, &Canonical(value))?;
+                }
+                map.end()
+            }
+            value => value.serialize(serializer),
+        }
+    }
+}
+
+/// Writes integral floats that are exactly representable as integers
+fn canonical_number(n: &Number) -> Number {
+    const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+    match n.as_f64() {
+        Some(f) if n.is_f64() && f.abs() < MAX_SAFE_INTEGER && f == (f as i64) as f64 => {
+            Number::from(f as i64)
+        }
+        _ => n.clone(),
+    }
+}
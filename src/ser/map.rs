@@ -1,10 +1,15 @@
 use serde::ser::SerializeMap;
 
-use crate::{Config, ser::value::WrapValue};
+use crate::{
+    Config,
+    ser::{map_key::MapKey, value::NestedValue},
+};
 
 pub struct WrapSerializeMap<'a, Map> {
     pub inner: Map,
     pub config: &'a Config,
+    /// Nesting depth of the elements
+    pub depth: usize,
 }
 
 impl<'a, Map> SerializeMap for WrapSerializeMap<'a, Map>
@@ -18,9 +23,11 @@ where
         &mut self,
         key: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.serialize_key(&WrapValue {
-            value: key,
+        // serde_json's key serializer rejects NaN and infinite float keys
+        self.inner.serialize_key(&MapKey {
+            key,
             config: self.config,
+            depth: self.depth,
         })
     }
 
@@ -28,9 +35,10 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.serialize_value(&WrapValue {
+        self.inner.serialize_value(&NestedValue {
             value,
             config: self.config,
+            depth: self.depth,
         })
     }
 
@@ -0,0 +1,342 @@
+// Map keys, which JSON requires to be strings
+
+use alloc::vec::Vec;
+use serde::ser::{Error as _, Serialize};
+
+use crate::{BytesFormat, Config, ser::serializer::Serializer};
+
+/// A map key paired with the config it serializes with
+///
+/// Byte keys are always written as strings. Bytes passed to `serialize_bytes`
+/// and sequences of integers in `0..=255`, such as a `Vec<u8>`, use the
+/// configured bytes format when it produces a string, and hex otherwise. See
+/// [`key_bytes_config`].
+pub(crate) struct MapKey<'a, T: ?Sized> {
+    pub(crate) key: &'a T,
+    pub(crate) config: &'a Config,
+    pub(crate) depth: usize,
+}
+
+impl<T> Serialize for MapKey<'_, T>
+where
+    T: ?Sized + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.key.serialize(MapKeySerializer {
+            inner: serializer,
+            config: self.config,
+            depth: self.depth,
+        })
+    }
+}
+
+struct MapKeySerializer<'a, S> {
+    inner: S,
+    config: &'a Config,
+    depth: usize,
+}
+
+impl<S> MapKeySerializer<'_, S>
+where
+    S: serde::Serializer,
+{
+    /// Writes byte keys as strings
+    ///
+    /// Formats that write arrays, objects or numbers (`Default`,
+    /// `LengthPrefixed`, `MongoExtended`, `NodeBuffer`, `HexAndLen` and
+    /// `HexOrInteger`) are replaced by `Hex` with the configured hex settings.
+    /// A `bytes_empty_format` of that kind is dropped.
+    fn serialize_key_bytes(self, v: &[u8]) -> Result<S::Ok, S::Error> {
+        let format = self
+            .config
+            .bytes_format_for_depth(self.depth)
+            .unwrap_or(self.config.bytes_format);
+        let format = match format.is_string() {
+            true => format,
+            false => BytesFormat::Hex,
+        };
+        let empty_format = self.config.bytes_empty_format.filter(|f| f.is_string());
+        Serializer::with_depth(self.inner, self.config, self.depth).serialize_bytes_as(
+            v,
+            format,
+            empty_format,
+        )
+    }
+}
+
+impl<'a, S> serde::Serializer for MapKeySerializer<'a, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = KeyBytes<'a, S>;
+    type SerializeTuple = KeyBytes<'a, S>;
+    type SerializeTupleStruct = S::SerializeTupleStruct;
+    type SerializeTupleVariant = S::SerializeTupleVariant;
+    type SerializeMap = S::SerializeMap;
+    type SerializeStruct = S::SerializeStruct;
+    type SerializeStructVariant = S::SerializeStructVariant;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Serializer::new(self.inner, self.config).serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.serialize_key_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        if let Some(format) = self.config.bytes_format_for_type_name(name) {
+            let config = self
+                .config
+                .clone_with_bytes_format(format)
+                .clear_bytes_formats_for_depth();
+            return self.inner.serialize_newtype_struct(
+                name,
+                &MapKey {
+                    key: value,
+                    config: &config,
+                    depth: self.depth,
+                },
+            );
+        }
+        self.inner.serialize_newtype_struct(
+            name,
+            &MapKey {
+                key: value,
+                config: self.config,
+                depth: self.depth,
+            },
+        )
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Serializer::with_depth(self.inner, self.config, self.depth).serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            value,
+        )
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(KeyBytes {
+            key: self,
+            bytes: Vec::with_capacity(len.unwrap_or(0)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.inner.serialize_tuple_struct(name, len)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.inner
+            .serialize_tuple_variant(name, variant_index, variant, len)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.inner.serialize_map(len)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.inner.serialize_struct(name, len)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.inner
+            .serialize_struct_variant(name, variant_index, variant, len)
+    }
+}
+
+/// Collects a sequence key of integers in `0..=255` and writes it as bytes
+struct KeyBytes<'a, S> {
+    key: MapKeySerializer<'a, S>,
+    bytes: Vec<u8>,
+}
+
+impl<S> KeyBytes<'_, S>
+where
+    S: serde::Serializer,
+{
+    fn push<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let byte = value
+            .serialize(serde_json::value::Serializer)
+            .ok()
+            .and_then(|v| v.as_u64())
+            .and_then(|n| u8::try_from(n).ok())
+            .ok_or_else(|| S::Error::custom("key must be a string or a sequence of bytes"))?;
+        self.bytes.push(byte);
+        Ok(())
+    }
+}
+
+impl<S> serde::ser::SerializeSeq for KeyBytes<'_, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.key.serialize_key_bytes(&self.bytes)
+    }
+}
+
+impl<S> serde::ser::SerializeTuple for KeyBytes<'_, S>
+where
+    S: serde::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.key.serialize_key_bytes(&self.bytes)
+    }
+}
@@ -1,4 +1,6 @@
+pub(crate) mod canonical;
 pub mod map;
+pub(crate) mod map_key;
 pub mod seq;
 pub(crate) mod ser_bytes;
 pub mod serializer;
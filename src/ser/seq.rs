@@ -1,10 +1,12 @@
 use serde::ser::SerializeSeq;
 
-use crate::{Config, ser::value::WrapValue};
+use crate::{Config, ser::value::NestedValue};
 
 pub struct WrapSerializeSeq<'a, Seq> {
     pub inner: Seq,
     pub config: &'a Config,
+    /// Nesting depth of the elements
+    pub depth: usize,
 }
 
 impl<'a, Seq> SerializeSeq for WrapSerializeSeq<'a, Seq>
@@ -18,9 +20,10 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.serialize_element(&WrapValue {
+        self.inner.serialize_element(&NestedValue {
             value,
             config: self.config,
+            depth: self.depth,
         })
     }
 
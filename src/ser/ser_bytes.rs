@@ -1,29 +1,234 @@
 // Bytes serialization utilities
 
-use crate::Config;
+use alloc::{format, string::String, vec::Vec};
+use serde::Serialize;
 
-/// Serializes bytes as a hexadecimal string "0x1234..." or "1234..."
+#[cfg(feature = "checksum")]
+use crate::codec::checksum;
+use crate::{
+    Base64Variant, BytesArrayRadix, Config,
+    codec::{base32, base36, hex, protobuf, rlp, uuencode},
+};
+
+/// Serializes bytes as a hexadecimal string "0x1234...", "1234..." or, with
+/// a group prefix, "0x120x34..." or, with a separator, "0x12-34..."
+///
+/// With `config.hex_reverse` the bytes are written in reverse order. Uppercase
+/// digits take precedence over a checksum, which `Config::validate` reports.
 pub(crate) fn ser_bytes_hex(config: &Config, value: &[u8]) -> String {
-    let hex_str = hex::encode(value);
+    let hex_str = if config.hex_reverse {
+        let reversed: Vec<u8> = value.iter().rev().copied().collect();
+        hex::encode(&reversed)
+    } else {
+        hex::encode(value)
+    };
+    let hex_str = if config.hex_uppercase {
+        hex_str.to_ascii_uppercase()
+    } else {
+        #[cfg(feature = "checksum")]
+        let hex_str = checksum::apply(config.hex_checksum, hex_str);
+        hex_str
+    };
+    let hex_str = match &config.hex_separator {
+        Some(separator) if config.hex_group_prefix.is_none() => {
+            // hex output is ASCII, so every chunk is valid UTF-8
+            let groups: Vec<&str> = hex_str
+                .as_bytes()
+                .chunks(config.hex_separator_nibbles)
+                .map(|group| core::str::from_utf8(group).unwrap())
+                .collect();
+            groups.join(separator)
+        }
+        _ => hex_str,
+    };
 
-    if config.hex_prefix {
+    if let Some(group_size) = config.hex_group_prefix {
+        let group_len = group_size * 2;
+        let mut out = String::with_capacity(hex_str.len() + hex_str.len().div_ceil(group_len) * 2);
+        for group in hex_str.as_bytes().chunks(group_len) {
+            out.push_str("0x");
+            // hex output is ASCII, so every chunk is valid UTF-8
+            out.push_str(core::str::from_utf8(group).unwrap());
+        }
+        out
+    } else if config.hex_prefix_for(value.len()) {
         format!("0x{}", hex_str)
     } else {
         hex_str
     }
 }
 
-/// Serializes bytes as a Base64 string
-///
-/// # Arguments
+/// Serializes one byte as a prefixed string with a fixed number of digits in `radix`
+pub(crate) fn ser_byte_radix(radix: BytesArrayRadix, b: u8) -> String {
+    match radix {
+        BytesArrayRadix::Hex => format!("0x{:02x}", b),
+        BytesArrayRadix::Octal => format!("0o{:03o}", b),
+        BytesArrayRadix::Binary => format!("0b{:08b}", b),
+    }
+}
+
+/// Serializes bytes as the hexadecimal string of their RLP encoding
+pub(crate) fn ser_bytes_rlp(config: &Config, value: &[u8]) -> String {
+    ser_bytes_hex(config, &rlp::encode(value))
+}
+
+/// Serializes bytes as the hexadecimal string of their varint-length-prefixed encoding
+pub(crate) fn ser_bytes_protobuf(config: &Config, value: &[u8]) -> String {
+    ser_bytes_hex(config, &protobuf::encode(value))
+}
+
+/// Serializes bytes as an RFC 4648 base32 string with padding
+pub(crate) fn ser_bytes_base32(value: &[u8]) -> String {
+    base32::encode(base32::RFC4648, value, true)
+}
+
+/// Serializes bytes as an unpadded z-base-32 string
+pub(crate) fn ser_bytes_zbase32(value: &[u8]) -> String {
+    base32::encode(base32::ZBASE32, value, false)
+}
+
+/// Serializes bytes as a lowercase base36 number, keeping leading zero bytes
+pub(crate) fn ser_bytes_base36(value: &[u8]) -> String {
+    base36::encode(value)
+}
+
+/// Serializes bytes as a data URI holding standard padded base64
+pub(crate) fn ser_bytes_data_uri(config: &Config, value: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        config.data_uri_mime_type,
+        ser_bytes_base64(value)
+    )
+}
+
+/// Serializes bytes as uuencoded lines
+pub(crate) fn ser_bytes_uuencode(value: &[u8]) -> String {
+    uuencode::encode(value)
+}
+
+/// Serializes up to 8 bytes as 0x-prefixed hex and longer bytes as standard padded Base64
 ///
-/// * `url_safe` - If true, uses URL-safe Base64 encoding, otherwise uses standard Base64
+/// Base64 that would read back as hex is replaced by hex.
+pub(crate) fn ser_bytes_hex_or_base64(value: &[u8]) -> String {
+    if value.len() > 8 {
+        let encoded = ser_bytes_base64(value);
+        if !hex::is_prefixed(&encoded) {
+            return encoded;
+        }
+    }
+    format!("0x{}", hex::encode(value))
+}
+
+/// Serializes bytes as a standard padded Base64 string
 pub(crate) fn ser_bytes_base64(value: &[u8]) -> String {
     use base64::{Engine as _, engine::general_purpose};
     general_purpose::STANDARD.encode(value)
 }
 
-pub(crate) fn ser_bytes_base64_url_safe(value: &[u8]) -> String {
+/// Serializes bytes as a Base64 string with the alphabet and padding of `variant`
+pub(crate) fn ser_bytes_base64_variant(variant: Base64Variant, value: &[u8]) -> String {
     use base64::{Engine as _, engine::general_purpose};
-    general_purpose::URL_SAFE.encode(value)
+
+    match (variant.url_safe, variant.pad) {
+        (false, true) => general_purpose::STANDARD.encode(value),
+        (false, false) => general_purpose::STANDARD_NO_PAD.encode(value),
+        (true, true) => general_purpose::URL_SAFE.encode(value),
+        (true, false) => general_purpose::URL_SAFE_NO_PAD.encode(value),
+    }
+}
+
+/// Serializes bytes as a Base64 string wrapped at 76 columns with CRLF line breaks
+pub(crate) fn ser_bytes_base64_mime(value: &[u8]) -> String {
+    const LINE_LEN: usize = 76;
+
+    let encoded = ser_bytes_base64(value);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / LINE_LEN * 2);
+    for (i, line) in encoded.as_bytes().chunks(LINE_LEN).enumerate() {
+        if i > 0 {
+            out.push_str("\r\n");
+        }
+        // base64 output is ASCII, so every chunk is valid UTF-8
+        out.push_str(core::str::from_utf8(line).unwrap());
+    }
+    out
+}
+
+/// Serializes bytes as an unpadded Base64 string in the bcrypt alphabet
+pub(crate) fn ser_bytes_base64_crypt(value: &[u8]) -> String {
+    use base64::{Engine as _, alphabet, engine::GeneralPurpose, engine::general_purpose};
+
+    const CRYPT: GeneralPurpose = GeneralPurpose::new(&alphabet::BCRYPT, general_purpose::NO_PAD);
+    CRYPT.encode(value)
+}
+
+/// Serializes bytes as a Base64 string using the configured custom engine
+///
+/// Returns `None` if no custom engine is configured
+pub(crate) fn ser_bytes_base64_custom(config: &Config, value: &[u8]) -> Option<String> {
+    config
+        .base64_engine
+        .as_ref()
+        .map(|engine| engine.encode(value))
+}
+
+/// A MongoDB extended JSON binary object
+#[derive(Serialize)]
+pub(crate) struct MongoBinary {
+    #[serde(rename = "$binary")]
+    binary: MongoBinaryBody,
+}
+
+#[derive(Serialize)]
+struct MongoBinaryBody {
+    base64: String,
+    #[serde(rename = "subType")]
+    sub_type: &'static str,
+}
+
+/// Serializes bytes as a MongoDB extended JSON binary with the generic subtype
+pub(crate) fn ser_bytes_mongo_extended(value: &[u8]) -> MongoBinary {
+    MongoBinary {
+        binary: MongoBinaryBody {
+            base64: ser_bytes_base64(value),
+            sub_type: "00",
+        },
+    }
+}
+
+/// A Node.js `Buffer.toJSON()` object
+#[derive(Serialize)]
+pub(crate) struct NodeBuffer<'a> {
+    r#type: &'static str,
+    data: &'a [u8],
+}
+
+/// Serializes bytes as a Node.js `Buffer.toJSON()` object
+pub(crate) fn ser_bytes_node_buffer(value: &[u8]) -> NodeBuffer<'_> {
+    NodeBuffer {
+        r#type: "Buffer",
+        data: value,
+    }
+}
+
+/// A byte count alongside the 0x-prefixed hex of the bytes
+#[derive(Serialize)]
+pub(crate) struct HexAndLen {
+    len: usize,
+    hex: String,
+}
+
+/// Serializes bytes as an object holding their count and 0x-prefixed hex
+pub(crate) fn ser_bytes_hex_and_len(value: &[u8]) -> HexAndLen {
+    HexAndLen {
+        len: value.len(),
+        hex: format!("0x{}", hex::encode(value)),
+    }
+}
+
+/// Prepends the configured bytes prefix (such as "b64:") to an encoded string
+pub(crate) fn with_bytes_prefix(config: &Config, encoded: String) -> String {
+    match &config.bytes_prefix {
+        Some(prefix) => format!("{}{}", prefix, encoded),
+        None => encoded,
+    }
 }
@@ -1,17 +1,30 @@
 // Serializer wrapper for serde_json::value::Serializer
 
+use alloc::{format, string::ToString as _, vec::Vec};
+use serde::{
+    Serialize as _,
+    ser::{Error as _, SerializeMap as _, SerializeSeq as _, SerializeStruct as _},
+};
+
 use crate::{
-    BytesFormat, Config,
+    BytesFormat, Config, ResultRepr, SubnormalPolicy, UnitRepr,
+    codec::crc32,
     ser::{
         map::WrapSerializeMap,
         seq::WrapSerializeSeq,
-        ser_bytes::{ser_bytes_base64, ser_bytes_base64_url_safe, ser_bytes_hex},
+        ser_bytes::{
+            ser_byte_radix, ser_bytes_base32, ser_bytes_base36, ser_bytes_base64_crypt,
+            ser_bytes_base64_custom, ser_bytes_base64_mime, ser_bytes_base64_variant,
+            ser_bytes_data_uri, ser_bytes_hex, ser_bytes_hex_and_len, ser_bytes_hex_or_base64,
+            ser_bytes_mongo_extended, ser_bytes_node_buffer, ser_bytes_protobuf, ser_bytes_rlp,
+            ser_bytes_uuencode, ser_bytes_zbase32, with_bytes_prefix,
+        },
         r#struct::WrapSerializeStruct,
         struct_variant::WrapSerializeStructVariant,
         tuple::WrapSerializeTuple,
         tuple_struct::WrapSerializeTupleStruct,
         tuple_variant::WrapSerializeTupleVariant,
-        value::WrapValue,
+        value::{NestedValue, WrapValue},
     },
 };
 
@@ -21,6 +34,8 @@ pub struct Serializer<'a, S> {
     pub inner: S,
     /// Configuration for serialization
     pub config: &'a Config,
+    /// Current nesting depth of arrays, objects and enum variants
+    pub depth: usize,
 }
 
 impl<'a, S> Serializer<'a, S>
@@ -29,7 +44,160 @@ where
 {
     /// Creates a new `Serializer` with custom config
     pub fn new(inner: S, config: &'a Config) -> Self {
-        Serializer { inner, config }
+        Serializer {
+            inner,
+            config,
+            depth: 0,
+        }
+    }
+
+    /// Creates a new `Serializer` that continues at the given nesting depth
+    pub(crate) fn with_depth(inner: S, config: &'a Config, depth: usize) -> Self {
+        Serializer {
+            inner,
+            config,
+            depth,
+        }
+    }
+
+    /// Writes the `0` that stands in for `-0.0` of either float type
+    ///
+    /// As with other floats `force_sign` does not apply, while
+    /// `numbers_as_strings` writes the zero as `"0"`.
+    fn serialize_normalized_zero(self) -> Result<S::Ok, S::Error> {
+        if self.config.numbers_as_strings {
+            return self.inner.serialize_str("0");
+        }
+        self.inner.serialize_u8(0)
+    }
+
+    /// Writes bytes like `serialize_bytes`, in `format` and, when they are
+    /// empty, in `empty_format` instead of the configured formats
+    pub(crate) fn serialize_bytes_as(
+        self,
+        v: &[u8],
+        format: BytesFormat,
+        empty_format: Option<BytesFormat>,
+    ) -> Result<S::Ok, S::Error> {
+        if let Some(placeholder) = &self.config.bytes_empty_placeholder
+            && v.is_empty()
+        {
+            return self.inner.serialize_str(placeholder);
+        }
+        if let Some(transform) = &self.config.bytes_transform {
+            let transformed = (transform.0)(v);
+            if transformed.is_empty() && self.config.bytes_empty_placeholder.is_some() {
+                // Would be written as the placeholder and read back as empty
+                return Err(S::Error::custom(
+                    "bytes transform produced empty bytes, which the empty placeholder cannot represent",
+                ));
+            }
+            return self.serialize_framed_bytes(&transformed, format, empty_format);
+        }
+        self.serialize_framed_bytes(v, format, empty_format)
+    }
+
+    /// Writes bytes in `format` after any transform, appending the checksum
+    /// suffix when enabled
+    fn serialize_framed_bytes(
+        self,
+        v: &[u8],
+        format: BytesFormat,
+        empty_format: Option<BytesFormat>,
+    ) -> Result<S::Ok, S::Error> {
+        if self.config.bytes_checksum_suffix {
+            let mut framed = Vec::with_capacity(v.len() + 4);
+            framed.extend_from_slice(v);
+            framed.extend_from_slice(&crc32::checksum(v).to_be_bytes());
+            return self.serialize_encoded_bytes(&framed, format, empty_format);
+        }
+        self.serialize_encoded_bytes(v, format, empty_format)
+    }
+
+    /// Writes bytes in `format`, or in `empty_format` when they are empty
+    fn serialize_encoded_bytes(
+        self,
+        v: &[u8],
+        format: BytesFormat,
+        empty_format: Option<BytesFormat>,
+    ) -> Result<S::Ok, S::Error> {
+        let format = match empty_format {
+            Some(empty_format) if v.is_empty() => empty_format,
+            _ => format,
+        };
+        let s = match format {
+            BytesFormat::Default => {
+                // Written as a sequence so that an enclosing serializer with a
+                // different config does not re-encode the bytes
+                let mut seq = self.inner.serialize_seq(Some(v.len()))?;
+                for b in v {
+                    if let Some(radix) = self.config.bytes_array_radix {
+                        seq.serialize_element(&ser_byte_radix(radix, *b))?;
+                    } else if self.config.bytes_default_signed {
+                        seq.serialize_element(&(*b as i8))?;
+                    } else {
+                        seq.serialize_element(b)?;
+                    }
+                }
+                return seq.end();
+            }
+            BytesFormat::LengthPrefixed => {
+                let mut seq = self.inner.serialize_seq(Some(v.len() + 1))?;
+                seq.serialize_element(&v.len())?;
+                for b in v {
+                    seq.serialize_element(b)?;
+                }
+                return seq.end();
+            }
+            BytesFormat::Hex => ser_bytes_hex(self.config, v),
+            BytesFormat::HexOrInteger if (1..=8).contains(&v.len()) => {
+                let mut be = [0u8; 8];
+                be[8 - v.len()..].copy_from_slice(v);
+                return self.inner.serialize_u64(u64::from_be_bytes(be));
+            }
+            BytesFormat::HexOrInteger => ser_bytes_hex(self.config, v),
+            BytesFormat::Rlp => ser_bytes_rlp(self.config, v),
+            BytesFormat::Protobuf => ser_bytes_protobuf(self.config, v),
+            BytesFormat::Base32 => ser_bytes_base32(v),
+            BytesFormat::ZBase32 => ser_bytes_zbase32(v),
+            BytesFormat::Base36 => ser_bytes_base36(v),
+            BytesFormat::DataUri => ser_bytes_data_uri(self.config, v),
+            BytesFormat::UuEncode => ser_bytes_uuencode(v),
+            BytesFormat::HexOrBase64 => ser_bytes_hex_or_base64(v),
+            BytesFormat::Utf8 => core::str::from_utf8(v)
+                .map_err(|e| S::Error::custom(format_args!("bytes are not valid UTF-8: {}", e)))?
+                .into(),
+            BytesFormat::MongoExtended => {
+                return ser_bytes_mongo_extended(v).serialize(self.inner);
+            }
+            BytesFormat::NodeBuffer => {
+                return ser_bytes_node_buffer(v).serialize(self.inner);
+            }
+            BytesFormat::HexAndLen => {
+                return ser_bytes_hex_and_len(v).serialize(self.inner);
+            }
+            #[allow(deprecated)]
+            BytesFormat::Base64(_) | BytesFormat::Base64UrlSafe => {
+                // The deprecated variant maps to a `Base64Variant`
+                let variant = format.base64_variant().unwrap();
+                ser_bytes_base64_variant(variant, v)
+            }
+            BytesFormat::Base64Mime => ser_bytes_base64_mime(v),
+            BytesFormat::Base64Crypt => ser_bytes_base64_crypt(v),
+            BytesFormat::Base64Custom => ser_bytes_base64_custom(self.config, v)
+                .ok_or_else(|| S::Error::custom("no custom base64 engine configured"))?,
+            BytesFormat::Codec(codec) => codec.encode(v),
+        };
+
+        let s = with_bytes_prefix(self.config, s);
+        if self.config.bytes_empty_placeholder.as_deref() == Some(s.as_str()) {
+            return Err(S::Error::custom(format_args!(
+                "{} bytes are written as the empty placeholder {:?}",
+                v.len(),
+                s
+            )));
+        }
+        self.inner.serialize_str(&s)
     }
 }
 
@@ -52,50 +220,142 @@ where
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_i8(v)
     }
 
     fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_i16(v)
     }
 
     fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_i32(v)
     }
 
     fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_i64(v)
     }
 
     fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_i128(v)
     }
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_u8(v)
     }
 
     fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_u16(v)
     }
 
     fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_u32(v)
     }
 
     fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_u64(v)
     }
 
     fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        if self.config.force_sign {
+            return self.inner.serialize_str(&format!("{:+}", v));
+        }
+        if self.config.numbers_as_strings {
+            return self.inner.collect_str(&v);
+        }
         self.inner.serialize_u128(v)
     }
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        let v = match self.config.subnormal_policy {
+            SubnormalPolicy::Zero if v.is_subnormal() => 0.0f32.copysign(v),
+            SubnormalPolicy::Error if v.is_subnormal() => {
+                return Err(S::Error::custom(format!("subnormal f32 {:e}", v)));
+            }
+            _ => v,
+        };
+        if self.config.normalize_negative_zero && v == 0.0 && v.is_sign_negative() {
+            return self.serialize_normalized_zero();
+        }
+        if self.config.numbers_as_strings && v.is_finite() {
+            // Written as serde_json writes the number, such as "1.0"
+            return self
+                .inner
+                .serialize_str(&serde_json::to_string(&v).map_err(S::Error::custom)?);
+        }
         self.inner.serialize_f32(v)
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        let v = match self.config.subnormal_policy {
+            SubnormalPolicy::Zero if v.is_subnormal() => 0.0f64.copysign(v),
+            SubnormalPolicy::Error if v.is_subnormal() => {
+                return Err(S::Error::custom(format!("subnormal f64 {:e}", v)));
+            }
+            _ => v,
+        };
+        if self.config.normalize_negative_zero && v == 0.0 && v.is_sign_negative() {
+            return self.serialize_normalized_zero();
+        }
+        if self.config.numbers_as_strings && v.is_finite() {
+            // Written as serde_json writes the number, such as "1.0"
+            return self
+                .inner
+                .serialize_str(&serde_json::to_string(&v).map_err(S::Error::custom)?);
+        }
         self.inner.serialize_f64(v)
     }
 
@@ -108,21 +368,12 @@ where
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        match self.config.bytes_format {
-            BytesFormat::Default => self.inner.serialize_bytes(v),
-            BytesFormat::Hex => {
-                let s = ser_bytes_hex(self.config, v);
-                self.inner.serialize_str(&s)
-            }
-            BytesFormat::Base64 => {
-                let s = ser_bytes_base64(v);
-                self.inner.serialize_str(&s)
-            }
-            BytesFormat::Base64UrlSafe => {
-                let s = ser_bytes_base64_url_safe(v);
-                self.inner.serialize_str(&s)
-            }
-        }
+        let format = self
+            .config
+            .bytes_format_for_depth(self.depth)
+            .unwrap_or(self.config.bytes_format);
+        let empty_format = self.config.bytes_empty_format;
+        self.serialize_bytes_as(v, format, empty_format)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
@@ -133,18 +384,26 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        self.inner.serialize_some(&WrapValue {
+        self.inner.serialize_some(&NestedValue {
             value,
             config: self.config,
+            depth: self.depth,
         })
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        self.inner.serialize_unit()
+        match self.config.unit_as {
+            UnitRepr::Null => self.inner.serialize_unit(),
+            UnitRepr::EmptyObject => self.inner.serialize_map(Some(0))?.end(),
+            UnitRepr::EmptyArray => self.inner.serialize_seq(Some(0))?.end(),
+        }
     }
 
     fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
-        self.inner.serialize_unit_struct(name)
+        match self.config.unit_as {
+            UnitRepr::Null => self.inner.serialize_unit_struct(name),
+            _ => self.serialize_unit(),
+        }
     }
 
     fn serialize_unit_variant(
@@ -165,7 +424,27 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        self.inner.serialize_newtype_struct(name, value)
+        if let Some(format) = self.config.bytes_format_for_type_name(name) {
+            let config = self
+                .config
+                .clone_with_bytes_format(format)
+                .clear_bytes_formats_for_depth();
+            return self.inner.serialize_newtype_struct(
+                name,
+                &WrapValue {
+                    value,
+                    config: &config,
+                },
+            );
+        }
+        self.inner.serialize_newtype_struct(
+            name,
+            &NestedValue {
+                value,
+                config: self.config,
+                depth: self.depth,
+            },
+        )
     }
 
     fn serialize_newtype_variant<T>(
@@ -178,8 +457,32 @@ where
     where
         T: ?Sized + serde::Serialize,
     {
-        self.inner
-            .serialize_newtype_variant(name, variant_index, variant, value)
+        if name == crate::result::RESULT_NAME && self.config.result_repr == ResultRepr::OkError {
+            let value = NestedValue {
+                value,
+                config: self.config,
+                depth: self.depth + 1,
+            };
+            let mut s = self.inner.serialize_struct("Result", 2)?;
+            if variant_index == 0 {
+                s.serialize_field("ok", &value)?;
+                s.serialize_field("error", &())?;
+            } else {
+                s.serialize_field("ok", &())?;
+                s.serialize_field("error", &value)?;
+            }
+            return s.end();
+        }
+        self.inner.serialize_newtype_variant(
+            name,
+            variant_index,
+            variant,
+            &NestedValue {
+                value,
+                config: self.config,
+                depth: self.depth + 1,
+            },
+        )
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
@@ -187,6 +490,7 @@ where
         Ok(WrapSerializeSeq {
             inner,
             config: self.config,
+            depth: self.depth + 1,
         })
     }
 
@@ -195,6 +499,7 @@ where
         Ok(WrapSerializeTuple {
             inner,
             config: self.config,
+            depth: self.depth + 1,
         })
     }
 
@@ -207,6 +512,7 @@ where
         Ok(WrapSerializeTupleStruct {
             inner,
             config: self.config,
+            depth: self.depth + 1,
         })
     }
 
@@ -223,6 +529,7 @@ where
         Ok(WrapSerializeTupleVariant {
             inner,
             config: self.config,
+            depth: self.depth + 2,
         })
     }
 
@@ -231,6 +538,7 @@ where
         Ok(WrapSerializeMap {
             inner,
             config: self.config,
+            depth: self.depth + 1,
         })
     }
 
@@ -243,6 +551,7 @@ where
         Ok(WrapSerializeStruct {
             inner,
             config: self.config,
+            depth: self.depth + 1,
         })
     }
 
@@ -259,13 +568,24 @@ where
         Ok(WrapSerializeStructVariant {
             inner,
             config: self.config,
+            depth: self.depth + 2,
         })
     }
 
     fn collect_str<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: ?Sized + std::fmt::Display,
+        T: ?Sized + core::fmt::Display,
     {
-        self.inner.collect_str(value)
+        if !self.config.display_hex_as_bytes {
+            return self.inner.collect_str(value);
+        }
+
+        let s = value.to_string();
+        if let Some(digits) = s.strip_prefix("0x")
+            && let Ok(bytes) = crate::codec::hex::decode(digits)
+        {
+            return self.serialize_bytes(&bytes);
+        }
+        self.inner.serialize_str(&s)
     }
 }
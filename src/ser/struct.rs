@@ -1,10 +1,12 @@
 use serde::ser::SerializeStruct;
 
-use crate::{Config, ser::value::WrapValue};
+use crate::{Config, ser::value::NestedValue};
 
 pub struct WrapSerializeStruct<'a, Struct> {
     pub inner: Struct,
     pub config: &'a Config,
+    /// Nesting depth of the elements
+    pub depth: usize,
 }
 
 impl<'a, Struct> SerializeStruct for WrapSerializeStruct<'a, Struct>
@@ -21,9 +23,10 @@ where
     ) -> Result<(), Self::Error> {
         self.inner.serialize_field(
             key,
-            &WrapValue {
+            &NestedValue {
                 value,
                 config: self.config,
+                depth: self.depth,
             },
         )
     }
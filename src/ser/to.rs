@@ -1,16 +1,26 @@
 // Serialization functions with configuration
 
-use serde_json::ser::{CompactFormatter, PrettyFormatter};
+use alloc::{string::String, vec::Vec};
 
-use crate::Config;
-// use crate::formatter::{CompactFormatter, PrettyFormatter};
+#[cfg(feature = "std")]
+use crate::Error;
+#[cfg(feature = "std")]
+use crate::formatter::{EscapingCompactFormatter, PrettyFormatter};
+use crate::ser::canonical::Canonical;
 use crate::ser::serializer::Serializer;
-use std::io::Write;
+use crate::vec_bytes::Bytes;
+use crate::{Config, Result};
+#[cfg(feature = "std")]
+use std::io::{BufWriter, Write};
 
 /// Serializes a value to a JSON string with the given configuration.
 ///
 /// This function uses a compact formatter (no pretty printing).
 ///
+/// The bytes format only applies to values that serialize as bytes. A plain
+/// `Vec<u8>` or `[u8; N]` serializes as a sequence of numbers and stays an
+/// array; use [`to_string_bytes`] or `serde_bytes` to encode it as bytes.
+///
 /// # Example
 ///
 /// ```
@@ -18,8 +28,9 @@ use std::io::Write;
 ///
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// let json = to_string(&vec![1u8, 2u8, 3u8], &config).unwrap();
+/// assert_eq!(json, "[1,2,3]");
 /// ```
-pub fn to_string<T>(value: &T, config: &Config) -> serde_json::Result<String>
+pub fn to_string<T>(value: &T, config: &Config) -> Result<String>
 where
     T: ?Sized + serde::Serialize,
 {
@@ -27,6 +38,60 @@ where
     Ok(String::from_utf8(bytes).unwrap())
 }
 
+/// Serializes a value to a `SmallString` with the given configuration.
+///
+/// Produces the same output as [`to_string`], but output that fits in the
+/// inline buffer `A` is kept on the stack instead of allocating. Requires the
+/// `smallstr` feature.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_small_string, Config};
+/// use smallstr::SmallString;
+///
+/// let config = Config::default().set_bytes_hex().enable_hex_prefix();
+/// let json: SmallString<[u8; 32]> = to_small_string(&(1, "a"), &config).unwrap();
+/// assert_eq!(json, r#"[1,"a"]"#);
+/// assert!(!json.spilled());
+/// ```
+#[cfg(feature = "smallstr")]
+pub fn to_small_string<A, T>(value: &T, config: &Config) -> Result<smallstr::SmallString<A>>
+where
+    A: smallvec::Array<Item = u8>,
+    T: ?Sized + serde::Serialize,
+{
+    let mut writer = smallvec::SmallVec::<A>::new();
+    to_writer(&mut writer, value, config)?;
+    if writer.spilled() {
+        // Reuses the heap buffer
+        let json = String::from_utf8(writer.into_vec()).unwrap();
+        Ok(smallstr::SmallString::from_string(json))
+    } else {
+        Ok(smallstr::SmallString::from_str(
+            core::str::from_utf8(&writer).unwrap(),
+        ))
+    }
+}
+
+/// Serializes a byte slice to a JSON string in the configured bytes format.
+///
+/// Unlike [`to_string`], the slice is always treated as bytes rather than as
+/// a sequence of numbers.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_string_bytes, Config};
+///
+/// let config = Config::default().set_bytes_hex().enable_hex_prefix();
+/// let json = to_string_bytes(&[1u8, 2u8, 3u8], &config).unwrap();
+/// assert_eq!(json, r#""0x010203""#);
+/// ```
+pub fn to_string_bytes(value: &[u8], config: &Config) -> Result<String> {
+    to_string(&Bytes(value), config)
+}
+
 /// Serializes a value to a pretty-printed JSON string with the given configuration.
 ///
 /// # Example
@@ -37,7 +102,8 @@ where
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// let json = to_string_pretty(&vec![1u8, 2u8, 3u8], &config).unwrap();
 /// ```
-pub fn to_string_pretty<T>(value: &T, config: &Config) -> serde_json::Result<String>
+#[cfg(feature = "std")]
+pub fn to_string_pretty<T>(value: &T, config: &Config) -> Result<String>
 where
     T: ?Sized + serde::Serialize,
 {
@@ -47,7 +113,9 @@ where
 
 /// Serializes a value to a JSON byte vector with the given configuration.
 ///
-/// This function uses a compact formatter (no pretty printing).
+/// This function uses a compact formatter (no pretty printing). Without the
+/// `std` feature it uses serde_json's own formatter, which ignores
+/// `escape_js_line_separators`.
 ///
 /// # Example
 ///
@@ -56,14 +124,22 @@ where
 ///
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// let json = to_vec(&vec![1u8, 2u8, 3u8], &config).unwrap();
+/// assert_eq!(json, b"[1,2,3]");
 /// ```
-pub fn to_vec<T>(value: &T, config: &Config) -> serde_json::Result<Vec<u8>>
+pub fn to_vec<T>(value: &T, config: &Config) -> Result<Vec<u8>>
 where
     T: ?Sized + serde::Serialize,
 {
-    let mut writer = Vec::with_capacity(128);
-    to_writer(&mut writer, value, config)?;
-    Ok(writer)
+    #[cfg(feature = "std")]
+    {
+        let mut writer = Vec::with_capacity(128);
+        to_writer(&mut writer, value, config)?;
+        Ok(writer)
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        Ok(serde_json::to_vec(&crate::WrapValue::new(value, config))?)
+    }
 }
 
 /// Serializes a value to a pretty-printed JSON byte vector with the given configuration.
@@ -76,7 +152,8 @@ where
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// let json = to_vec_pretty(&vec![1u8, 2u8, 3u8], &config).unwrap();
 /// ```
-pub fn to_vec_pretty<T>(value: &T, config: &Config) -> serde_json::Result<Vec<u8>>
+#[cfg(feature = "std")]
+pub fn to_vec_pretty<T>(value: &T, config: &Config) -> Result<Vec<u8>>
 where
     T: ?Sized + serde::Serialize,
 {
@@ -87,7 +164,10 @@ where
 
 /// Serializes a value to a JSON writer with the given configuration.
 ///
-/// This function uses a compact formatter (no pretty printing).
+/// This function uses a compact formatter (no pretty printing). Output is
+/// written in many small pieces, so wrap unbuffered writers such as a
+/// `TcpStream` in a `BufWriter`, or use [`to_writer_buffered`]. The writer is
+/// not flushed; use [`to_writer_flush`] to flush it afterwards.
 ///
 /// # Example
 ///
@@ -98,15 +178,72 @@ where
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// to_writer(&mut stdout(), &vec![1u8, 2u8, 3u8], &config).unwrap();
 /// ```
-pub fn to_writer<W, T>(writer: &mut W, value: &T, config: &Config) -> serde_json::Result<()>
+#[cfg(feature = "std")]
+pub fn to_writer<W, T>(writer: &mut W, value: &T, config: &Config) -> Result<()>
 where
     W: ?Sized + Write,
     T: ?Sized + serde::Serialize,
 {
-    let formatter = CompactFormatter;
+    if config.json5_output {
+        let formatter = PrettyFormatter::compact(config);
+        let mut ser = serde_json::Serializer::with_formatter(writer, formatter);
+        return Ok(value.serialize(Serializer::new(&mut ser, config))?);
+    }
+
+    let formatter = EscapingCompactFormatter::new(config);
     let mut ser = serde_json::Serializer::with_formatter(writer, formatter);
     let serializer = Serializer::new(&mut ser, config);
-    value.serialize(serializer)
+    Ok(value.serialize(serializer)?)
+}
+
+/// Serializes a value to a JSON writer with the given configuration and flushes it.
+///
+/// Like [`to_writer`], but calls `writer.flush()` afterwards so that output
+/// held by a buffering writer such as a `BufWriter` is not lost.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_writer_flush, Config};
+/// use std::io::{stdout, BufWriter};
+///
+/// let config = Config::default().set_bytes_hex().enable_hex_prefix();
+/// let mut writer = BufWriter::new(stdout());
+/// to_writer_flush(&mut writer, &vec![1u8, 2u8, 3u8], &config).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer_flush<W, T>(writer: &mut W, value: &T, config: &Config) -> Result<()>
+where
+    W: ?Sized + Write,
+    T: ?Sized + serde::Serialize,
+{
+    to_writer(writer, value, config)?;
+    writer
+        .flush()
+        .map_err(|e| Error::Json(serde_json::Error::io(e)))
+}
+
+/// Serializes a value to a JSON writer through an internal `BufWriter` with the given configuration.
+///
+/// This function uses a compact formatter (no pretty printing) and flushes
+/// the buffer before returning.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_writer_buffered, Config};
+/// use std::io::stdout;
+///
+/// let config = Config::default().set_bytes_hex().enable_hex_prefix();
+/// to_writer_buffered(&mut stdout(), &vec![1u8, 2u8, 3u8], &config).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer_buffered<W, T>(writer: &mut W, value: &T, config: &Config) -> Result<()>
+where
+    W: ?Sized + Write,
+    T: ?Sized + serde::Serialize,
+{
+    to_writer_flush(&mut BufWriter::new(writer), value, config)
 }
 
 /// Serializes a value to a pretty-printed JSON writer with the given configuration.
@@ -120,15 +257,101 @@ where
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// to_writer_pretty(&mut stdout(), &vec![1u8, 2u8, 3u8], &config).unwrap();
 /// ```
-pub fn to_writer_pretty<W, T>(writer: &mut W, value: &T, config: &Config) -> serde_json::Result<()>
+#[cfg(feature = "std")]
+pub fn to_writer_pretty<W, T>(writer: &mut W, value: &T, config: &Config) -> Result<()>
 where
     W: ?Sized + Write,
     T: ?Sized + serde::Serialize,
 {
-    let formatter = PrettyFormatter::new();
+    let formatter = PrettyFormatter::new(config);
     let mut ser = serde_json::Serializer::with_formatter(writer, formatter);
     let serializer = Serializer::new(&mut ser, config);
-    value.serialize(serializer)
+    Ok(value.serialize(serializer)?)
+}
+
+/// Serializes values as newline-delimited JSON with the given configuration.
+///
+/// Each value is written compactly on its own line, followed by `\n`, into a
+/// single buffer.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_ndjson_vec, Config};
+///
+/// let config = Config::default();
+/// let ndjson = to_ndjson_vec(&[1, 2, 3], &config).unwrap();
+/// assert_eq!(ndjson, b"1\n2\n3\n");
+/// ```
+#[cfg(feature = "std")]
+pub fn to_ndjson_vec<T>(values: &[T], config: &Config) -> Result<Vec<u8>>
+where
+    T: serde::Serialize,
+{
+    let mut writer = Vec::with_capacity(128 * values.len());
+    for value in values {
+        to_writer(&mut writer, value, config)?;
+        writer.push(b'\n');
+    }
+    Ok(writer)
+}
+
+/// Serializes values from an iterator as newline-delimited JSON to a writer.
+///
+/// Each value is written compactly on its own line as the iterator yields
+/// it, so the values are never held in memory together. Output goes through
+/// an internal `BufWriter`, which writes to `writer` whenever its buffer
+/// fills, and `writer` is flushed before returning. Items may be values or
+/// references.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_writer_iter, Config};
+///
+/// let config = Config::default();
+/// let mut out = Vec::new();
+/// to_writer_iter(&mut out, (1..=3).map(|n| n * 10), &config).unwrap();
+/// assert_eq!(out, b"10\n20\n30\n");
+/// ```
+#[cfg(feature = "std")]
+pub fn to_writer_iter<W, I>(writer: &mut W, iter: I, config: &Config) -> Result<()>
+where
+    W: ?Sized + Write,
+    I: IntoIterator,
+    I::Item: serde::Serialize,
+{
+    let io_error = |e| Error::Json(serde_json::Error::io(e));
+
+    let mut writer = BufWriter::new(writer);
+    for value in iter {
+        to_writer(&mut writer, &value, config)?;
+        writer.write_all(b"\n").map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)
+}
+
+/// Serializes values as newline-delimited JSON in parallel with the given configuration.
+///
+/// Produces the same output as [`to_ndjson_vec`], serializing values on the
+/// rayon thread pool and joining the lines in order. Requires the `rayon`
+/// feature.
+#[cfg(feature = "rayon")]
+pub fn to_ndjson_vec_par<T>(values: &[T], config: &Config) -> Result<Vec<u8>>
+where
+    T: serde::Serialize + Sync,
+{
+    use rayon::prelude::*;
+
+    let lines = values
+        .par_iter()
+        .map(|value| {
+            let mut line = to_vec(value, config)?;
+            line.push(b'\n');
+            Ok(line)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(lines.concat())
 }
 
 /// Serializes a value to a `serde_json::Value` with the given configuration.
@@ -141,18 +364,85 @@ where
 /// let config = Config::default().set_bytes_hex().enable_hex_prefix();
 /// let value = to_value(&vec![1u8, 2u8, 3u8], &config).unwrap();
 /// ```
-pub fn to_value<T>(value: &T, config: &Config) -> serde_json::Result<serde_json::Value>
+pub fn to_value<T>(value: &T, config: &Config) -> Result<serde_json::Value>
 where
     T: ?Sized + serde::Serialize,
 {
     let ser = serde_json::value::Serializer;
 
     let serializer = Serializer::new(ser, config);
-    value.serialize(serializer)
+    Ok(value.serialize(serializer)?)
+}
+
+/// Serializes a value to a canonical JSON string with the given configuration.
+///
+/// The output is suitable for signing: it is compact, object keys are sorted
+/// by their UTF-16 code units at every level, and floats with an integral
+/// value (such as `1.0`) are written as integers. This follows RFC 8785 (JCS)
+/// except that other floats keep serde_json's shortest round-trip form, which
+/// differs from JCS for exponents (`1e+21` is written `1e21`). Bytes and other
+/// options of `config` apply as in [`to_string`].
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_canonical_string, Config};
+/// use serde_json::json;
+///
+/// let config = Config::default();
+/// let json = to_canonical_string(&json!({"b": 1.0, "a": [true]}), &config).unwrap();
+/// assert_eq!(json, r#"{"a":[true],"b":1}"#);
+/// ```
+pub fn to_canonical_string<T>(value: &T, config: &Config) -> Result<String>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let value = to_value(value, config)?;
+    to_string(&Canonical(&value), config)
+}
+
+/// Serializes a value to compact JSON escaped as a single CSV or TSV cell.
+///
+/// Following RFC 4180, the JSON is wrapped in double quotes with every inner
+/// quote doubled when it contains a quote, comma, tab or line break; other
+/// output, such as a bare number, is returned as is.
+///
+/// # Example
+///
+/// ```
+/// use serde_json_ext::{to_csv_cell, Config};
+/// use serde_json::json;
+///
+/// let cell = to_csv_cell(&json!({"a": 1}), &Config::default()).unwrap();
+/// assert_eq!(cell, r#""{""a"":1}""#);
+/// ```
+pub fn to_csv_cell<T>(value: &T, config: &Config) -> Result<String>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let json = to_string(value, config)?;
+    if !json.contains(['"', ',', '\t', '\r', '\n']) {
+        return Ok(json);
+    }
+    let mut cell = String::with_capacity(json.len() + 2);
+    cell.push('"');
+    for c in json.chars() {
+        if c == '"' {
+            cell.push('"');
+        }
+        cell.push(c);
+    }
+    cell.push('"');
+    Ok(cell)
 }
 
 #[cfg(test)]
 mod tests {
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+    };
     use serde_json::json;
 
     use super::*;
@@ -242,6 +532,23 @@ mod tests {
         assert_eq!(result, r#"{"data":"AQID_w=="}"#);
     }
 
+    #[test]
+    fn test_to_string_bytes_base64_crypt() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![0x00, 0x10, 0x83, 0xff],
+        };
+
+        let config = Config::default().set_bytes_base64_crypt();
+        let result = to_string(&test_data, &config).unwrap();
+        assert_eq!(result, r#"{"data":"./AB9u"}"#);
+    }
+
     #[test]
     fn test_to_string_bytes_empty() {
         #[derive(serde::Serialize)]
@@ -583,4 +890,1033 @@ mod tests {
         });
         assert_eq!(value, expect);
     }
+
+    #[test]
+    fn test_to_value_returns_value() {
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+        let value: serde_json::Value =
+            to_value(serde_bytes::Bytes::new(&[1, 2, 3]), &config).unwrap();
+        assert_eq!(value, serde_json::Value::String("0x010203".to_string()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_value_map_with_byte_values() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert("a", serde_bytes::ByteBuf::from(vec![1, 2]));
+        map.insert("b", serde_bytes::ByteBuf::from(vec![255]));
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let value = to_value(&map, &config).unwrap();
+        assert_eq!(value, json!({"a": "0x0102", "b": "0xff"}));
+
+        let value = to_value(&vec![map], &config).unwrap();
+        assert_eq!(value, json!([{"a": "0x0102", "b": "0xff"}]));
+    }
+
+    #[test]
+    fn test_to_string_byte_map_keys() {
+        use alloc::collections::BTreeMap;
+
+        let mut map: BTreeMap<Vec<u8>, i32> = BTreeMap::new();
+        map.insert(vec![0x01, 0x02], 1);
+        map.insert(vec![0xff], 2);
+
+        // Formats that do not write strings fall back to hex for keys
+        let config = Config::default();
+        let json = to_string(&map, &config).unwrap();
+        assert_eq!(json, r#"{"0102":1,"ff":2}"#);
+
+        let config = Config::default()
+            .set_bytes_hex_and_len()
+            .enable_hex_prefix();
+        let json = to_string(&map, &config).unwrap();
+        assert_eq!(json, r#"{"0x0102":1,"0xff":2}"#);
+
+        let config = Config::default().set_bytes_base64();
+        let json = to_string(&map, &config).unwrap();
+        assert_eq!(json, r#"{"AQI=":1,"/w==":2}"#);
+
+        let mut map = BTreeMap::new();
+        map.insert(serde_bytes::ByteBuf::from(vec![0x01, 0x02]), 1);
+        let json = to_string(&map, &config).unwrap();
+        assert_eq!(json, r#"{"AQI=":1}"#);
+
+        let mut map = BTreeMap::new();
+        map.insert(vec![256u16], 1);
+        assert!(to_string(&map, &config).is_err());
+    }
+
+    #[test]
+    fn test_to_value_nested_byte_fields() {
+        #[derive(serde::Serialize)]
+        struct Inner {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        #[derive(serde::Serialize)]
+        enum Kind {
+            Struct {
+                #[serde(with = "serde_bytes")]
+                data: Vec<u8>,
+            },
+            Tuple(serde_bytes::ByteBuf, u8),
+        }
+
+        #[derive(serde::Serialize)]
+        struct Outer {
+            inner: Inner,
+            list: Vec<Inner>,
+            tuple: (serde_bytes::ByteBuf, u8),
+            kinds: Vec<Kind>,
+        }
+
+        let value = Outer {
+            inner: Inner { data: vec![1] },
+            list: vec![Inner { data: vec![2] }, Inner { data: vec![3] }],
+            tuple: (serde_bytes::ByteBuf::from(vec![4]), 5),
+            kinds: vec![
+                Kind::Struct { data: vec![6] },
+                Kind::Tuple(serde_bytes::ByteBuf::from(vec![7]), 8),
+            ],
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result = to_value(&value, &config).unwrap();
+        assert_eq!(
+            result,
+            json!({
+                "inner": {"data": "0x01"},
+                "list": [{"data": "0x02"}, {"data": "0x03"}],
+                "tuple": ["0x04", 5],
+                "kinds": [{"Struct": {"data": "0x06"}}, {"Tuple": ["0x07", 8]}],
+            })
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_matches_serde_json() {
+        #[derive(serde::Serialize)]
+        struct Nested {
+            values: Vec<u32>,
+            empty: Vec<u32>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            name: String,
+            nested: Nested,
+            list: Vec<Nested>,
+        }
+
+        let test_data = TestStruct {
+            name: "test".to_string(),
+            nested: Nested {
+                values: vec![1, 2, 3],
+                empty: vec![],
+            },
+            list: vec![Nested {
+                values: vec![4],
+                empty: vec![],
+            }],
+        };
+
+        let config = Config::default();
+        let result = to_string_pretty(&test_data, &config).unwrap();
+        assert_eq!(result, serde_json::to_string_pretty(&test_data).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_compact_arrays_inline() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            short: Vec<u8>,
+            long: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            short: vec![1, 2, 3],
+            long: (0u8..20u8).collect(),
+            data: vec![4, 5, 6],
+        };
+
+        let config = Config::default().enable_compact_arrays_inline(8);
+        let result = to_string_pretty(&test_data, &config).unwrap();
+
+        let long = (0u8..20u8)
+            .map(|v| format!("    {}", v))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let expected = format!(
+            "{{\n  \"short\": [1, 2, 3],\n  \"long\": [\n{}\n  ],\n  \"data\": [4, 5, 6]\n}}",
+            long
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_compact_arrays_inline_nested() {
+        let test_data = vec![vec![1u8, 2u8], vec![3u8]];
+
+        let config = Config::default().enable_compact_arrays_inline(8);
+        let result = to_string_pretty(&test_data, &config).unwrap();
+        assert_eq!(result, "[\n  [1, 2],\n  [3]\n]");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_align_object_values() {
+        #[derive(serde::Serialize)]
+        struct Inner {
+            x: u8,
+            long_y: u8,
+        }
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            id: u8,
+            name: &'static str,
+            inner: Inner,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            id: 1,
+            name: "node",
+            inner: Inner { x: 2, long_y: 3 },
+            data: vec![4, 5],
+        };
+
+        let config = Config::default()
+            .enable_align_object_values()
+            .enable_compact_arrays_inline(4);
+        let result = to_string_pretty(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            "{\n  \"id\":    1,\n  \"name\":  \"node\",\n  \"inner\": {\n    \"x\":      2,\n    \"long_y\": 3\n  },\n  \"data\":  [4, 5]\n}"
+        );
+
+        // Arrays are laid out as usual
+        let config = Config::default().enable_align_object_values();
+        let result = to_string_pretty(&vec![json!({"a": 1, "bb": 2})], &config).unwrap();
+        assert_eq!(result, "[\n  {\n    \"a\":  1,\n    \"bb\": 2\n  }\n]");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_compact_threshold() {
+        let value = json!({
+            "point": {"x": 1, "y": 2},
+            "tags": ["a", "b"],
+            "large": {"name": "a longer value", "items": [1, 2, 3]}
+        });
+
+        let config = Config::default().set_pretty_compact_threshold(20);
+        let result = to_string_pretty(&value, &config).unwrap();
+        assert_eq!(
+            result,
+            "{\n  \"large\": {\n    \"items\": [1,2,3],\n    \"name\": \"a longer value\"\n  },\n  \"point\": {\"x\":1,\"y\":2},\n  \"tags\": [\"a\",\"b\"]\n}"
+        );
+
+        // A container fitting exactly stays inline, one byte longer expands
+        let config = Config::default().set_pretty_compact_threshold(13);
+        let result = to_string_pretty(&json!({"x": 1, "y": 2}), &config).unwrap();
+        assert_eq!(result, "{\"x\":1,\"y\":2}");
+        let config = Config::default().set_pretty_compact_threshold(12);
+        let result = to_string_pretty(&json!({"x": 1, "y": 2}), &config).unwrap();
+        assert_eq!(result, "{\n  \"x\": 1,\n  \"y\": 2\n}");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_json5_output() {
+        #[derive(serde::Serialize)]
+        struct Inner {
+            max_len: u32,
+            #[serde(rename = "$ref")]
+            reference: &'static str,
+        }
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            name: &'static str,
+            #[serde(rename = "my-key")]
+            my_key: bool,
+            inner: Inner,
+            list: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            name: "node",
+            my_key: true,
+            inner: Inner {
+                max_len: 8,
+                reference: "a\"b",
+            },
+            list: vec![1, 2],
+        };
+
+        let config = Config::default().enable_json5_output();
+        let result = to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            r#"{name:"node","my-key":true,inner:{max_len:8,$ref:"a\"b"},list:[1,2]}"#
+        );
+
+        let config = config.enable_json5_trailing_commas();
+        let result = to_string_pretty(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            "{\n  name: \"node\",\n  \"my-key\": true,\n  inner: {\n    max_len: 8,\n    $ref: \"a\\\"b\",\n  },\n  list: [\n    1,\n    2,\n  ],\n}"
+        );
+
+        // Keys that are not identifiers keep their quotes
+        let value = json!({"1": 1, "": 2, "a b": 3, "_x1": 4});
+        let result = to_string(&value, &Config::default().enable_json5_output()).unwrap();
+        assert_eq!(result, r#"{"":2,"1":1,_x1:4,"a b":3}"#);
+
+        // Without JSON5 output, trailing commas have no effect
+        let config = Config::default().enable_json5_trailing_commas();
+        let result = to_string_pretty(&json!({"a": [1]}), &config).unwrap();
+        assert_eq!(result, "{\n  \"a\": [\n    1\n  ]\n}");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_pretty_indent_tabs() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            list: Vec<u8>,
+        }
+
+        let test_data = TestStruct {
+            data: vec![1, 2],
+            list: vec![3],
+        };
+
+        let config = Config::default().set_bytes_hex().set_indent_tabs(1);
+        let result = to_string_pretty(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            "{\n\t\"data\": \"0102\",\n\t\"list\": [\n\t\t3\n\t]\n}"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_non_finite_float_key() {
+        use ordered_float::OrderedFloat;
+        use std::collections::HashMap;
+
+        let config = Config::default().set_bytes_hex();
+
+        for key in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let map = HashMap::from([(OrderedFloat(key), 1u8)]);
+
+            let err = to_string(&map, &config).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "float key must be finite (got NaN or +/-inf)"
+            );
+
+            assert!(to_string_pretty(&map, &config).is_err());
+            assert!(to_value(&map, &config).is_err());
+        }
+
+        let map = HashMap::from([(OrderedFloat(1.5), 1u8)]);
+        assert_eq!(to_string(&map, &config).unwrap(), r#"{"1.5":1}"#);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_writer_buffered_fewer_writes() {
+        struct CountingWriter {
+            data: Vec<u8>,
+            writes: usize,
+        }
+
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.writes += 1;
+                self.data.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let value: Vec<Vec<u8>> = (0..100).map(|i| vec![i, i + 1]).collect();
+        let config = Config::default();
+
+        let mut unbuffered = CountingWriter {
+            data: Vec::new(),
+            writes: 0,
+        };
+        to_writer(&mut unbuffered, &value, &config).unwrap();
+
+        let mut buffered = CountingWriter {
+            data: Vec::new(),
+            writes: 0,
+        };
+        to_writer_buffered(&mut buffered, &value, &config).unwrap();
+
+        assert_eq!(buffered.data, unbuffered.data);
+        assert!(buffered.writes <= 2);
+        assert!(unbuffered.writes > 100);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_writer_flush() {
+        #[derive(Default)]
+        struct FlushTracker {
+            data: Vec<u8>,
+            flushed: bool,
+        }
+
+        impl Write for FlushTracker {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.flushed = false;
+                self.data.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.flushed = true;
+                Ok(())
+            }
+        }
+
+        let config = Config::default();
+
+        let mut writer = FlushTracker::default();
+        to_writer(&mut writer, &[1, 2, 3], &config).unwrap();
+        assert!(!writer.flushed);
+
+        let mut writer = FlushTracker::default();
+        to_writer_flush(&mut writer, &[1, 2, 3], &config).unwrap();
+        assert!(writer.flushed);
+        assert_eq!(writer.data, b"[1,2,3]");
+
+        let mut writer = FlushTracker::default();
+        to_writer_buffered(&mut writer, &[1, 2, 3], &config).unwrap();
+        assert!(writer.flushed);
+        assert_eq!(writer.data, b"[1,2,3]");
+    }
+
+    #[test]
+    fn test_to_string_plain_u8_sequences_stay_numeric() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            list: Vec<u8>,
+            array: [u8; 3],
+        }
+
+        let test_data = TestStruct {
+            data: vec![1, 2, 255],
+            list: vec![1, 2, 255],
+            array: [1, 2, 255],
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result = to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            r#"{"data":"0x0102ff","list":[1,2,255],"array":[1,2,255]}"#
+        );
+
+        let config = Config::default().set_bytes_base64();
+        let result = to_value(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            json!({"data": "AQL/", "list": [1, 2, 255], "array": [1, 2, 255]})
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_overridden_config() {
+        use crate::WrapValue;
+        use serde::Serialize;
+        use std::sync::LazyLock;
+
+        static BASE64: LazyLock<Config> = LazyLock::new(|| Config::default().set_bytes_base64());
+        static DEFAULT: LazyLock<Config> = LazyLock::new(Config::default);
+
+        fn base64_bytes<S: serde::Serializer>(
+            v: &[u8],
+            s: S,
+        ) -> core::result::Result<S::Ok, S::Error> {
+            WrapValue::new(serde_bytes::Bytes::new(v), &BASE64).serialize(s)
+        }
+
+        fn default_bytes<S: serde::Serializer>(
+            v: &[u8],
+            s: S,
+        ) -> core::result::Result<S::Ok, S::Error> {
+            WrapValue::new(serde_bytes::Bytes::new(v), &DEFAULT).serialize(s)
+        }
+
+        #[derive(serde::Serialize)]
+        struct Inner {
+            #[serde(serialize_with = "base64_bytes")]
+            signature: Vec<u8>,
+            #[serde(serialize_with = "default_bytes")]
+            raw: Vec<u8>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Outer {
+            #[serde(with = "serde_bytes")]
+            hash: Vec<u8>,
+            inner: Inner,
+        }
+
+        let test_data = Outer {
+            hash: vec![0xab, 0xcd],
+            inner: Inner {
+                signature: vec![1, 2, 255],
+                raw: vec![1, 2],
+            },
+        };
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result = to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            r#"{"hash":"0xabcd","inner":{"signature":"AQL/","raw":[1,2]}}"#
+        );
+
+        let result = to_value(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            json!({"hash": "0xabcd", "inner": {"signature": "AQL/", "raw": [1, 2]}})
+        );
+
+        let bytes = serde_bytes::Bytes::new(&[1, 2, 255]);
+        let result = to_string(&WrapValue::new(bytes, &BASE64), &config).unwrap();
+        assert_eq!(result, r#""AQL/""#);
+    }
+
+    #[test]
+    fn test_to_string_numbers_as_strings() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            a: i32,
+            b: u64,
+            c: i128,
+            d: f64,
+            e: f32,
+            f: f64,
+        }
+
+        let test_data = TestStruct {
+            a: -5,
+            b: u64::MAX,
+            c: i128::MIN,
+            d: 1.0,
+            e: 0.1,
+            f: f64::NAN,
+        };
+
+        let config = Config::default().enable_numbers_as_strings();
+        assert!(config.numbers_as_strings());
+        assert_eq!(
+            to_string(&test_data, &config).unwrap(),
+            r#"{"a":"-5","b":"18446744073709551615","c":"-170141183460469231731687303715884105728","d":"1.0","e":"0.1","f":null}"#
+        );
+        assert_eq!(
+            to_value(&test_data, &config).unwrap(),
+            serde_json::json!({
+                "a": "-5",
+                "b": "18446744073709551615",
+                "c": "-170141183460469231731687303715884105728",
+                "d": "1.0",
+                "e": "0.1",
+                "f": null,
+            })
+        );
+
+        // Signed output wins for integers
+        let config = config.enable_force_sign();
+        assert_eq!(to_string(&(5, 1.5), &config).unwrap(), r#"["+5","1.5"]"#);
+    }
+
+    #[test]
+    fn test_to_string_force_sign() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            a: i32,
+            b: i64,
+            c: u8,
+            d: i128,
+            e: f64,
+        }
+
+        let test_data = TestStruct {
+            a: 5,
+            b: -5,
+            c: 0,
+            d: i128::MIN,
+            e: 1.5,
+        };
+
+        let config = Config::default().enable_force_sign();
+        let result = to_string(&test_data, &config).unwrap();
+        assert_eq!(
+            result,
+            r#"{"a":"+5","b":"-5","c":"+0","d":"-170141183460469231731687303715884105728","e":1.5}"#
+        );
+
+        assert_eq!(to_value(&5i32, &config).unwrap(), json!("+5"));
+        assert_eq!(to_string(&5i32, &Config::default()).unwrap(), "5");
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_to_string_bytes_hex_checksum() {
+        use crate::ChecksumAlgo;
+
+        let address = hex::decode("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap();
+        let address = serde_bytes::Bytes::new(&address);
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .enable_hex_prefix()
+            .enable_hex_eip55();
+        let result = to_string(&address, &config).unwrap();
+        assert_eq!(result, r#""0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed""#);
+
+        let config = Config::default()
+            .set_bytes_hex_checksum(ChecksumAlgo::Eip1191 { chain_id: 30 })
+            .enable_hex_prefix();
+        let result = to_string(&address, &config).unwrap();
+        assert_eq!(result, r#""0x5aaEB6053f3e94c9b9a09f33669435E7ef1bEAeD""#);
+
+        let config = config.set_bytes_hex_checksum(ChecksumAlgo::Eip1191 { chain_id: 31 });
+        let result = to_string(&address, &config).unwrap();
+        assert_eq!(result, r#""0x5aAeb6053F3e94c9b9A09F33669435E7EF1BEaEd""#);
+    }
+
+    #[test]
+    fn test_to_string_bytes_hex_uppercase() {
+        let data = serde_bytes::Bytes::new(&[0xde, 0xad, 0xbe, 0xef]);
+
+        let config = Config::default()
+            .set_bytes_format_hex_uppercase_prefix_combo()
+            .unwrap();
+        let result = to_string(&data, &config).unwrap();
+        assert_eq!(result, r#""0xDEADBEEF""#);
+        assert_eq!(
+            crate::from_str_bytes(&result, &config).unwrap(),
+            [0xde, 0xad, 0xbe, 0xef]
+        );
+
+        let config = config.disable_hex_prefix();
+        let result = to_string(&data, &config).unwrap();
+        assert_eq!(result, r#""DEADBEEF""#);
+    }
+
+    #[test]
+    fn test_to_string_unit_as() {
+        use crate::UnitRepr;
+
+        #[derive(serde::Serialize)]
+        struct Marker;
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            unit: (),
+            marker: Marker,
+            option: Option<()>,
+        }
+
+        let value = TestStruct {
+            unit: (),
+            marker: Marker,
+            option: Some(()),
+        };
+
+        let config = Config::default();
+        assert_eq!(config.unit_as(), UnitRepr::Null);
+        let result = to_string(&value, &config).unwrap();
+        assert_eq!(result, r#"{"unit":null,"marker":null,"option":null}"#);
+
+        let config = Config::default().set_unit_as(UnitRepr::EmptyObject);
+        let result = to_string(&value, &config).unwrap();
+        assert_eq!(result, r#"{"unit":{},"marker":{},"option":{}}"#);
+        assert_eq!(to_value(&(), &config).unwrap(), json!({}));
+
+        let config = Config::default().set_unit_as(UnitRepr::EmptyArray);
+        let result = to_string(&value, &config).unwrap();
+        assert_eq!(result, r#"{"unit":[],"marker":[],"option":[]}"#);
+        assert_eq!(to_value(&(), &config).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_to_string_display_bytes() {
+        use core::fmt;
+
+        // A byte type that serializes through `Display`
+        struct Hash([u8; 2]);
+
+        impl fmt::Display for Hash {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "0x{:02x}{:02x}", self.0[0], self.0[1])
+            }
+        }
+
+        impl serde::Serialize for Hash {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                s: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                s.collect_str(self)
+            }
+        }
+
+        let value = (Hash([0xab, 0xcd]), core::net::Ipv4Addr::LOCALHOST);
+
+        // The bytes format does not apply to `Display` output
+        let config = Config::default().set_bytes_base64();
+        let result = to_string(&value, &config).unwrap();
+        assert_eq!(result, r#"["0xabcd","127.0.0.1"]"#);
+
+        let config = config.enable_display_hex_as_bytes();
+        assert!(config.display_hex_as_bytes());
+        let result = to_string(&value, &config).unwrap();
+        assert_eq!(result, r#"["q80=","127.0.0.1"]"#);
+        assert_eq!(
+            to_value(&value, &config).unwrap(),
+            json!(["q80=", "127.0.0.1"])
+        );
+    }
+
+    #[test]
+    fn test_to_string_normalize_negative_zero() {
+        let config = Config::default();
+        assert_eq!(to_string(&-0.0f64, &config).unwrap(), "-0.0");
+
+        let config = config.enable_normalize_negative_zero();
+        assert!(config.normalize_negative_zero());
+        assert_eq!(to_string(&-0.0f64, &config).unwrap(), "0");
+        assert_eq!(to_string(&-0.0f32, &config).unwrap(), "0");
+        assert_eq!(to_string(&0.0f64, &config).unwrap(), "0.0");
+        assert_eq!(to_string(&-1.5f64, &config).unwrap(), "-1.5");
+        assert_eq!(to_value(&-0.0f64, &config).unwrap(), serde_json::json!(0));
+        assert!(to_value(&-0.0f64, &config).unwrap().is_u64());
+
+        let config = config.set_subnormal_policy(crate::SubnormalPolicy::Zero);
+        assert_eq!(to_string(&-f64::from_bits(1), &config).unwrap(), "0");
+
+        // Integer options do not apply to the zero written for a float
+        let config = config.enable_force_sign();
+        assert_eq!(to_string(&-0.0f64, &config).unwrap(), "0");
+        assert_eq!(to_string(&-0.0f32, &config).unwrap(), "0");
+        assert_eq!(to_string(&5u8, &config).unwrap(), r#""+5""#);
+        let config = config.enable_numbers_as_strings();
+        assert_eq!(to_string(&-0.0f64, &config).unwrap(), r#""0""#);
+    }
+
+    #[test]
+    fn test_to_string_subnormal_policy() {
+        use crate::SubnormalPolicy;
+
+        let subnormal = f64::MIN_POSITIVE / 2.0;
+        assert!(subnormal.is_subnormal());
+
+        let config = Config::default();
+        assert_eq!(config.subnormal_policy(), SubnormalPolicy::Keep);
+        let result = to_string(&subnormal, &config).unwrap();
+        assert_eq!(result, serde_json::to_string(&subnormal).unwrap());
+
+        let config = Config::default().set_subnormal_policy(SubnormalPolicy::Zero);
+        assert_eq!(to_string(&subnormal, &config).unwrap(), "0.0");
+        assert_eq!(to_string(&-subnormal, &config).unwrap(), "-0.0");
+        assert_eq!(
+            to_string(&vec![1.5, subnormal], &config).unwrap(),
+            "[1.5,0.0]"
+        );
+
+        let config = Config::default().set_subnormal_policy(SubnormalPolicy::Error);
+        let err = to_string(&vec![subnormal], &config).unwrap_err();
+        assert!(err.to_string().contains("subnormal f64"));
+        assert!(to_string(&f64::MIN_POSITIVE, &config).is_ok());
+        assert!(to_string(&(f32::MIN_POSITIVE / 2.0), &config).is_err());
+    }
+
+    #[test]
+    fn test_to_string_bytes() {
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        assert_eq!(to_string(&vec![1u8, 2, 255], &config).unwrap(), "[1,2,255]");
+        assert_eq!(
+            to_string_bytes(&[1u8, 2, 255], &config).unwrap(),
+            r#""0x0102ff""#
+        );
+        assert_eq!(
+            to_string_bytes(&[1u8, 2, 255], &Config::default().set_bytes_base64()).unwrap(),
+            r#""AQL/""#
+        );
+        assert_eq!(
+            to_string_bytes(&[1u8, 2, 255], &Config::default()).unwrap(),
+            "[1,2,255]"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_string_escape_js_line_separators() {
+        let mut map = alloc::collections::BTreeMap::new();
+        map.insert("key\u{2029}", "line\u{2028}break");
+
+        let config = Config::default();
+        let result = to_string(&map, &config).unwrap();
+        assert_eq!(result, "{\"key\u{2029}\":\"line\u{2028}break\"}");
+
+        let config = Config::default().enable_escape_js_line_separators();
+        let result = to_string(&map, &config).unwrap();
+        assert_eq!(result, r#"{"key\u2029":"line\u2028break"}"#);
+
+        let result = to_string_pretty(&map, &config).unwrap();
+        assert_eq!(result, "{\n  \"key\\u2029\": \"line\\u2028break\"\n}");
+
+        let parsed: alloc::collections::BTreeMap<String, String> =
+            serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["key\u{2029}"], "line\u{2028}break");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_canonical_string() {
+        use std::collections::HashMap;
+
+        let mut first = HashMap::new();
+        let mut second = HashMap::new();
+        for (key, value) in [("b", 2.0), ("a", 1.5), ("c", 3.0)] {
+            first.insert(key, value);
+        }
+        for (key, value) in [("c", 3.0), ("a", 1.5), ("b", 2.0)] {
+            second.insert(key, value);
+        }
+
+        let config = Config::default();
+        let first = to_canonical_string(&first, &config).unwrap();
+        let second = to_canonical_string(&second, &config).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first, r#"{"a":1.5,"b":2,"c":3}"#);
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            zeta: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+            nested: serde_json::Value,
+        }
+
+        let value = TestStruct {
+            zeta: vec![1],
+            data: vec![1, 2],
+            nested: json!({"\u{e000}": 1, "\u{10000}": 2, "B": 3, "a": 4}),
+        };
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let result = to_canonical_string(&value, &config).unwrap();
+        assert_eq!(
+            result,
+            "{\"data\":\"0x0102\",\"nested\":{\"B\":3,\"a\":4,\"\u{10000}\":2,\"\u{e000}\":1},\"zeta\":[1]}"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_cell() {
+        #[derive(serde::Serialize)]
+        struct Row {
+            name: &'static str,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let row = Row {
+            name: "say \"hi\", then\tleave",
+            data: vec![1, 2],
+        };
+        let cell = to_csv_cell(&row, &config).unwrap();
+        assert_eq!(
+            cell,
+            r#""{""name"":""say \""hi\"", then\tleave"",""data"":""0x0102""}""#
+        );
+
+        // Unquoting the cell gives back the JSON
+        let inner = cell[1..cell.len() - 1].replace("\"\"", "\"");
+        assert_eq!(inner, to_string(&row, &config).unwrap());
+
+        assert_eq!(to_csv_cell(&42, &config).unwrap(), "42");
+        assert_eq!(to_csv_cell(&[1, 2], &config).unwrap(), r#""[1,2]""#);
+    }
+
+    #[test]
+    fn test_to_string_hex_prefix_lengths() {
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            #[serde(with = "serde_bytes")]
+            address: Vec<u8>,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let value = TestStruct {
+            address: vec![0xab; 20],
+            data: vec![0x01; 7],
+        };
+
+        let config = Config::default()
+            .set_bytes_hex()
+            .set_hex_prefix_lengths(vec![20, 32]);
+        let result = to_value(&value, &config).unwrap();
+        assert_eq!(result["address"], json!(format!("0x{}", "ab".repeat(20))));
+        assert_eq!(result["data"], json!("01010101010101"));
+
+        let config = config.enable_hex_prefix().set_hex_prefix_lengths(vec![]);
+        let result = to_value(&value, &config).unwrap();
+        assert_eq!(result["address"], json!("ab".repeat(20)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_ndjson_vec() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Record {
+            id: u32,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let records: Vec<Record> = (0..3)
+            .map(|id| Record {
+                id,
+                data: vec![id as u8; 2],
+            })
+            .collect();
+        let config = Config::default().set_bytes_hex();
+
+        let ndjson = to_ndjson_vec(&records, &config).unwrap();
+        let text = String::from_utf8(ndjson).unwrap();
+        assert!(text.ends_with('\n'));
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], r#"{"id":1,"data":"0101"}"#);
+        for (line, record) in lines.iter().zip(&records) {
+            let parsed: Record = crate::from_str(line, &config).unwrap();
+            assert_eq!(&parsed, record);
+        }
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(
+            to_ndjson_vec_par(&records, &config).unwrap(),
+            text.as_bytes()
+        );
+
+        assert!(to_ndjson_vec::<Record>(&[], &config).unwrap().is_empty());
+    }
+
+    #[cfg(feature = "smallstr")]
+    #[test]
+    fn test_to_small_string() {
+        use smallstr::SmallString;
+
+        #[derive(serde::Serialize)]
+        struct Record {
+            id: u32,
+            #[serde(with = "serde_bytes")]
+            data: Vec<u8>,
+        }
+
+        let config = Config::default().set_bytes_hex().enable_hex_prefix();
+        let short = Record {
+            id: 1,
+            data: vec![0x01, 0x02],
+        };
+        let json: SmallString<[u8; 32]> = to_small_string(&short, &config).unwrap();
+        assert_eq!(json, to_string(&short, &config).unwrap());
+        assert!(!json.spilled());
+
+        let long = Record {
+            id: 2,
+            data: vec![0xab; 32],
+        };
+        let json: SmallString<[u8; 32]> = to_small_string(&long, &config).unwrap();
+        assert_eq!(json, to_string(&long, &config).unwrap());
+        assert!(json.spilled());
+
+        let config = config.enable_json5_output();
+        let json: SmallString<[u8; 32]> = to_small_string(&short, &config).unwrap();
+        assert_eq!(json, to_string(&short, &config).unwrap());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_to_writer_iter() {
+        use std::io::Write;
+
+        /// Counts the writes that reach the underlying writer
+        struct CountingWriter {
+            out: Vec<u8>,
+            writes: usize,
+            flushed: bool,
+        }
+
+        impl Write for CountingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.writes += 1;
+                self.out.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.flushed = true;
+                Ok(())
+            }
+        }
+
+        let mut writer = CountingWriter {
+            out: Vec::new(),
+            writes: 0,
+            flushed: false,
+        };
+        let config = Config::default().set_bytes_hex();
+        let values = (0..1000u32).map(|id| json!({"id": id, "name": format!("item {}", id)}));
+        to_writer_iter(&mut writer, values, &config).unwrap();
+
+        let text = String::from_utf8(writer.out).unwrap();
+        assert!(text.ends_with('\n'));
+        assert_eq!(text.lines().count(), 1000);
+        assert_eq!(
+            text.lines().nth(999).unwrap(),
+            r#"{"id":999,"name":"item 999"}"#
+        );
+        assert!(writer.writes > 1 && writer.writes < 1000);
+        assert!(writer.flushed);
+
+        // References work as items too
+        let records = [vec![1u8], vec![2u8]];
+        let mut out = Vec::new();
+        to_writer_iter(&mut out, &records, &config).unwrap();
+        assert_eq!(out, b"[1]\n[2]\n");
+    }
 }
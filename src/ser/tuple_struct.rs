@@ -1,10 +1,12 @@
 use serde::ser::SerializeTupleStruct;
 
-use crate::{Config, ser::value::WrapValue};
+use crate::{Config, ser::value::NestedValue};
 
 pub struct WrapSerializeTupleStruct<'a, Tup> {
     pub inner: Tup,
     pub config: &'a Config,
+    /// Nesting depth of the elements
+    pub depth: usize,
 }
 
 impl<'a, Tup> SerializeTupleStruct for WrapSerializeTupleStruct<'a, Tup>
@@ -18,9 +20,10 @@ where
         &mut self,
         value: &T,
     ) -> Result<(), Self::Error> {
-        self.inner.serialize_field(&WrapValue {
+        self.inner.serialize_field(&NestedValue {
             value,
             config: self.config,
+            depth: self.depth,
         })
     }
 
@@ -1,10 +1,22 @@
 use crate::{Config, ser::serializer::Serializer};
 
+/// A value paired with the config it serializes with
+///
+/// Serializing a `WrapValue` inside a document written with another config
+/// applies its own config to the wrapped value, which allows one document to
+/// mix byte encodings.
 pub struct WrapValue<'a, T: ?Sized> {
     pub value: &'a T,
     pub config: &'a Config,
 }
 
+impl<'a, T: ?Sized> WrapValue<'a, T> {
+    /// Wraps a value so that it serializes with `config`
+    pub fn new(value: &'a T, config: &'a Config) -> Self {
+        WrapValue { value, config }
+    }
+}
+
 impl<'a, T: ?Sized> serde::ser::Serialize for WrapValue<'a, T>
 where
     T: serde::ser::Serialize,
@@ -17,3 +29,23 @@ where
             .serialize(Serializer::new(serializer, self.config))
     }
 }
+
+/// A value inside a container, serialized at the container's nesting depth
+pub(crate) struct NestedValue<'a, T: ?Sized> {
+    pub(crate) value: &'a T,
+    pub(crate) config: &'a Config,
+    pub(crate) depth: usize,
+}
+
+impl<'a, T: ?Sized> serde::ser::Serialize for NestedValue<'a, T>
+where
+    T: serde::ser::Serialize,
+{
+    fn serialize<S2>(&self, serializer: S2) -> Result<S2::Ok, S2::Error>
+    where
+        S2: serde::ser::Serializer,
+    {
+        self.value
+            .serialize(Serializer::with_depth(serializer, self.config, self.depth))
+    }
+}
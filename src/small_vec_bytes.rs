@@ -0,0 +1,67 @@
+// Serde helpers for `smallvec::SmallVec` byte buffers
+
+use alloc::vec::Vec;
+use core::{fmt, marker::PhantomData};
+
+use serde::de::{SeqAccess, Visitor};
+use smallvec::{Array, SmallVec};
+
+/// Serializes a `SmallVec<[u8; N]>` as bytes, so that it follows the configured bytes format
+///
+/// Use with `#[serde(with = "serde_json_ext::small_vec_bytes")]`.
+pub fn serialize<S, A>(value: &SmallVec<A>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    A: Array<Item = u8>,
+{
+    serializer.serialize_bytes(value)
+}
+
+/// Deserializes a `SmallVec<[u8; N]>` from bytes in the configured bytes format
+///
+/// Input longer than the inline capacity spills to the heap.
+pub fn deserialize<'de, D, A>(deserializer: D) -> Result<SmallVec<A>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    A: Array<Item = u8>,
+{
+    struct SmallVecVisitor<A>(PhantomData<A>);
+
+    impl<'de, A> Visitor<'de> for SmallVecVisitor<A>
+    where
+        A: Array<Item = u8>,
+    {
+        type Value = SmallVec<A>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("bytes")
+        }
+
+        fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(SmallVec::from_slice(v))
+        }
+
+        fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            Ok(SmallVec::from_vec(v))
+        }
+
+        fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+        where
+            V: SeqAccess<'de>,
+        {
+            let mut bytes = SmallVec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(b) = seq.next_element()? {
+                bytes.push(b);
+            }
+            Ok(bytes)
+        }
+    }
+
+    deserializer.deserialize_byte_buf(SmallVecVisitor(PhantomData))
+}
@@ -0,0 +1,159 @@
+// Serde helpers for `Duration` and `SystemTime`
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, de::Error as _, ser::Error as _};
+
+/// Unit of a serialized time value
+#[derive(Clone, Copy)]
+enum Unit {
+    Secs,
+    Millis,
+    Nanos,
+}
+
+impl Unit {
+    /// Whole units in `d`, truncated toward zero
+    fn count(self, d: Duration) -> Option<u64> {
+        match self {
+            Unit::Secs => Some(d.as_secs()),
+            Unit::Millis => d.as_millis().try_into().ok(),
+            Unit::Nanos => d.as_nanos().try_into().ok(),
+        }
+    }
+
+    fn duration(self, n: u64) -> Duration {
+        match self {
+            Unit::Secs => Duration::from_secs(n),
+            Unit::Millis => Duration::from_millis(n),
+            Unit::Nanos => Duration::from_nanos(n),
+        }
+    }
+}
+
+fn serialize_duration<S>(
+    d: Duration,
+    unit: Unit,
+    as_string: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let n = unit
+        .count(d)
+        .ok_or_else(|| S::Error::custom("duration does not fit in a u64"))?;
+    if as_string {
+        serializer.collect_str(&n)
+    } else {
+        serializer.serialize_u64(n)
+    }
+}
+
+fn deserialize_duration<'de, D>(
+    unit: Unit,
+    as_string: bool,
+    deserializer: D,
+) -> Result<Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let n = if as_string {
+        let s = String::deserialize(deserializer)?;
+        s.parse::<u64>().map_err(D::Error::custom)?
+    } else {
+        u64::deserialize(deserializer)?
+    };
+    Ok(unit.duration(n))
+}
+
+fn serialize_system_time<S>(
+    t: SystemTime,
+    unit: Unit,
+    as_string: bool,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let d = t
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| S::Error::custom("time is before the Unix epoch"))?;
+    serialize_duration(d, unit, as_string, serializer)
+}
+
+fn deserialize_system_time<'de, D>(
+    unit: Unit,
+    as_string: bool,
+    deserializer: D,
+) -> Result<SystemTime, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let d = deserialize_duration(unit, as_string, deserializer)?;
+    UNIX_EPOCH
+        .checked_add(d)
+        .ok_or_else(|| D::Error::custom("time is out of range"))
+}
+
+macro_rules! time_modules {
+    ($ty:ty, $ser:ident, $de:ident, $($name:ident => $unit:expr, $as_string:expr, $doc:literal;)*) => {
+        $(
+            #[doc = $doc]
+            pub mod $name {
+                use super::super::*;
+
+                #[doc = concat!("Serializes a `", stringify!($ty), "` as a count of whole units")]
+                pub fn serialize<S>(value: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: serde::Serializer,
+                {
+                    $ser(*value, $unit, $as_string, serializer)
+                }
+
+                #[doc = concat!("Deserializes a `", stringify!($ty), "` from a count of whole units")]
+                pub fn deserialize<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+                where
+                    D: serde::Deserializer<'de>,
+                {
+                    $de($unit, $as_string, deserializer)
+                }
+            }
+        )*
+    };
+}
+
+/// `Duration` as a count of seconds, milliseconds or nanoseconds
+///
+/// Use with `#[serde(with = "serde_json_ext::time::duration::millis")]`. The
+/// count is truncated toward zero and must fit in a `u64`. The `_str`
+/// variants write the count as a string, for consumers that lose precision
+/// past 2^53.
+pub mod duration {
+    time_modules! {
+        Duration, serialize_duration, deserialize_duration,
+        secs => Unit::Secs, false, "Whole seconds as a number";
+        millis => Unit::Millis, false, "Whole milliseconds as a number";
+        nanos => Unit::Nanos, false, "Nanoseconds as a number";
+        secs_str => Unit::Secs, true, "Whole seconds as a string";
+        millis_str => Unit::Millis, true, "Whole milliseconds as a string";
+        nanos_str => Unit::Nanos, true, "Nanoseconds as a string";
+    }
+}
+
+/// `SystemTime` as seconds, milliseconds or nanoseconds since the Unix epoch
+///
+/// Use with `#[serde(with = "serde_json_ext::time::system_time::millis")]`.
+/// Times before the epoch fail to serialize. The `_str` variants write the
+/// count as a string.
+pub mod system_time {
+    time_modules! {
+        SystemTime, serialize_system_time, deserialize_system_time,
+        secs => Unit::Secs, false, "Whole seconds since the epoch as a number";
+        millis => Unit::Millis, false, "Whole milliseconds since the epoch as a number";
+        nanos => Unit::Nanos, false, "Nanoseconds since the epoch as a number";
+        secs_str => Unit::Secs, true, "Whole seconds since the epoch as a string";
+        millis_str => Unit::Millis, true, "Whole milliseconds since the epoch as a string";
+        nanos_str => Unit::Nanos, true, "Nanoseconds since the epoch as a string";
+    }
+}
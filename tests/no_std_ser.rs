@@ -0,0 +1,30 @@
+// Serialization entry points available without the `std` feature
+//
+// Run with `cargo test --no-default-features --features no-std-ser --test no_std_ser`.
+
+use serde_json_ext::{Config, to_string, to_string_bytes, to_value, to_vec};
+
+#[derive(serde::Serialize)]
+struct TestStruct {
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+    name: &'static str,
+}
+
+#[test]
+fn test_serialize_without_std() {
+    let value = TestStruct {
+        data: vec![1, 2, 255],
+        name: "test",
+    };
+    let config = Config::default().set_bytes_hex().enable_hex_prefix();
+
+    let expected = r#"{"data":"0x0102ff","name":"test"}"#;
+    assert_eq!(to_string(&value, &config).unwrap(), expected);
+    assert_eq!(to_vec(&value, &config).unwrap(), expected.as_bytes());
+    assert_eq!(
+        to_value(&value, &config).unwrap(),
+        serde_json::json!({"data": "0x0102ff", "name": "test"})
+    );
+    assert_eq!(to_string_bytes(&[1, 2], &config).unwrap(), r#""0x0102""#);
+}